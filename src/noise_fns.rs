@@ -2,8 +2,10 @@ pub use self::{
     cache::*, combiners::*, generators::*, modifiers::*, selectors::*, transformers::*,
 };
 use alloc::boxed::Box;
+use core::convert::TryInto;
 
 mod cache;
+pub mod combinators;
 mod combiners;
 mod generators;
 mod modifiers;
@@ -47,6 +49,250 @@ where
     }
 }
 
+/// Trait for noise functions that can report their gradient alongside their value.
+///
+/// The default implementation estimates the gradient with central differences, so any noise
+/// function can opt in with an empty `impl`; the crate's own noise functions all do. Functions
+/// that can compute an analytic gradient should override
+/// [`get_with_derivative`](Self::get_with_derivative).
+pub trait NoiseFnDeriv<const DIM: usize>: NoiseFn<f64, DIM> {
+    /// Returns the value at `point` and its partial derivative along each axis.
+    fn get_with_derivative(&self, point: [f64; DIM]) -> (f64, [f64; DIM]) {
+        const STEP: f64 = 1e-4;
+
+        let mut derivative = [0.0; DIM];
+
+        for (axis, slope) in derivative.iter_mut().enumerate() {
+            let mut ahead = point;
+            let mut behind = point;
+            ahead[axis] += STEP;
+            behind[axis] -= STEP;
+
+            *slope = (self.get(ahead) - self.get(behind)) / (2.0 * STEP);
+        }
+
+        (self.get(point), derivative)
+    }
+}
+
+impl<M, const DIM: usize> NoiseFnDeriv<DIM> for &M
+where
+    M: NoiseFnDeriv<DIM> + ?Sized,
+{
+    #[inline]
+    fn get_with_derivative(&self, point: [f64; DIM]) -> (f64, [f64; DIM]) {
+        M::get_with_derivative(*self, point)
+    }
+}
+
+impl<M, const DIM: usize> NoiseFnDeriv<DIM> for Box<M>
+where
+    M: NoiseFnDeriv<DIM> + ?Sized,
+{
+    #[inline]
+    fn get_with_derivative(&self, point: [f64; DIM]) -> (f64, [f64; DIM]) {
+        M::get_with_derivative(self, point)
+    }
+}
+
+/// Trait for noise functions with a single-precision sampling path, used by fast preview builds
+/// such as [`PlaneMapBuilder::set_fast_preview`](crate::utils::PlaneMapBuilder::set_fast_preview).
+///
+/// The default implementation widens the point and calls [`NoiseFn::get`], so any noise function
+/// can opt in with an empty `impl`; the crate's own noise functions all do. Functions with a
+/// cheaper `f32` evaluation should override [`get_f32`](Self::get_f32).
+pub trait NoiseFnF32<const DIM: usize>: NoiseFn<f64, DIM> {
+    /// Returns the value at `point`, computed in single precision where supported.
+    fn get_f32(&self, point: [f32; DIM]) -> f32 {
+        self.get(point.map(f64::from)) as f32
+    }
+}
+
+impl<M, const DIM: usize> NoiseFnF32<DIM> for &M
+where
+    M: NoiseFnF32<DIM> + ?Sized,
+{
+    #[inline]
+    fn get_f32(&self, point: [f32; DIM]) -> f32 {
+        M::get_f32(*self, point)
+    }
+}
+
+impl<M, const DIM: usize> NoiseFnF32<DIM> for Box<M>
+where
+    M: NoiseFnF32<DIM> + ?Sized,
+{
+    #[inline]
+    fn get_f32(&self, point: [f32; DIM]) -> f32 {
+        M::get_f32(self, point)
+    }
+}
+
+/// Trait for noise functions that accept a per-sample seed, such as stochastic sources used with
+/// [`PlaneMapBuilder::set_cell_seed`](crate::utils::PlaneMapBuilder::set_cell_seed).
+///
+/// The default implementation ignores the seed and calls [`NoiseFn::get`].
+pub trait SeededNoiseFn<const DIM: usize>: NoiseFn<f64, DIM> {
+    /// Returns the value at `point` for the given deterministic `seed`.
+    fn get_seeded(&self, point: [f64; DIM], seed: u64) -> f64 {
+        let _ = seed;
+        self.get(point)
+    }
+}
+
+impl<M, const DIM: usize> SeededNoiseFn<DIM> for &M
+where
+    M: SeededNoiseFn<DIM> + ?Sized,
+{
+    #[inline]
+    fn get_seeded(&self, point: [f64; DIM], seed: u64) -> f64 {
+        M::get_seeded(*self, point, seed)
+    }
+}
+
+impl<M, const DIM: usize> SeededNoiseFn<DIM> for Box<M>
+where
+    M: SeededNoiseFn<DIM> + ?Sized,
+{
+    #[inline]
+    fn get_seeded(&self, point: [f64; DIM], seed: u64) -> f64 {
+        M::get_seeded(self, point, seed)
+    }
+}
+
+/// Implements the extension traits with their default bodies for the crate's noise functions,
+/// in every dimension where the type implements `NoiseFn<f64, DIM>`. Types whose own parameters
+/// include the dimension list it after a `;`, and the bounds their definition requires follow in
+/// brackets.
+macro_rules! impl_default_extensions {
+    ($($ty:ident $(<$($param:ident),* $(; $dim:ident)?>)? $([$($bound:tt)*])?),* $(,)?) => {$(
+        impl<$($($param,)*)? const DIM: usize> NoiseFnDeriv<DIM> for $ty$(<$($param,)* $($dim)?>)?
+        where
+            Self: NoiseFn<f64, DIM>,
+            $($($bound)*)?
+        {
+        }
+
+        impl<$($($param,)*)? const DIM: usize> NoiseFnF32<DIM> for $ty$(<$($param,)* $($dim)?>)?
+        where
+            Self: NoiseFn<f64, DIM>,
+            $($($bound)*)?
+        {
+        }
+    )*};
+}
+
+impl_default_extensions!(
+    Abs<T, Source; DIM> [Source: NoiseFn<T, DIM>],
+    Add<T, Source1, Source2; DIM> [Source1: NoiseFn<T, DIM>, Source2: NoiseFn<T, DIM>],
+    BasicMulti<T>,
+    Billow<T>,
+    Blend<T, Source1, Source2, Control; DIM> [
+        Source1: NoiseFn<T, DIM>,
+        Source2: NoiseFn<T, DIM>,
+        Control: NoiseFn<T, DIM>,
+    ],
+    Cache<Source>,
+    Checkerboard,
+    Clamp<T, Source; DIM> [Source: NoiseFn<T, DIM>],
+    Constant,
+    Curve<T, Source; DIM> [Source: NoiseFn<T, DIM>],
+    Cylinders,
+    Displace<Source, XDisplace, YDisplace, ZDisplace, UDisplace>,
+    Exponent<T, Source; DIM> [Source: NoiseFn<T, DIM>],
+    Fbm<T>,
+    HybridMulti<T>,
+    Max<T, Source1, Source2; DIM> [Source1: NoiseFn<T, DIM>, Source2: NoiseFn<T, DIM>],
+    Min<T, Source1, Source2; DIM> [Source1: NoiseFn<T, DIM>, Source2: NoiseFn<T, DIM>],
+    Multiply<T, Source1, Source2; DIM> [Source1: NoiseFn<T, DIM>, Source2: NoiseFn<T, DIM>],
+    Negate<T, Source; DIM> [Source: NoiseFn<T, DIM>],
+    OpenSimplex,
+    Perlin,
+    PerlinSurflet,
+    Power<T, Source1, Source2; DIM> [Source1: NoiseFn<T, DIM>, Source2: NoiseFn<T, DIM>],
+    RidgedMulti<T>,
+    RotatePoint<Source>,
+    ScaleBias<T, Source; DIM> [Source: NoiseFn<T, DIM>],
+    ScalePoint<Source>,
+    Select<T, Source1, Source2, Control; DIM> [
+        Source1: NoiseFn<T, DIM>,
+        Source2: NoiseFn<T, DIM>,
+        Control: NoiseFn<T, DIM>,
+    ],
+    Simplex,
+    SuperSimplex,
+    Terrace<T, Source; DIM> [Source: NoiseFn<T, DIM>],
+    TranslatePoint<Source>,
+    Turbulence<Source, F> [F: Default + Seedable],
+    Value,
+    Worley,
+);
+
+/// Object-safe trait for samplers whose dimension is only known at runtime, such as noise graphs
+/// assembled from a script or configuration file.
+///
+/// Wrap any [`NoiseFn<f64, DIM>`](NoiseFn) in a [`DynNoiseFn`] to use it as a `DynSampler`.
+pub trait DynSampler {
+    /// Returns the number of coordinates [`sample`](Self::sample) expects.
+    fn dim(&self) -> usize;
+
+    /// Returns the value at `point`, which must have [`dim`](Self::dim) coordinates.
+    fn sample(&self, point: &[f64]) -> f64;
+}
+
+impl<M> DynSampler for Box<M>
+where
+    M: DynSampler + ?Sized,
+{
+    #[inline]
+    fn dim(&self) -> usize {
+        M::dim(self)
+    }
+
+    #[inline]
+    fn sample(&self, point: &[f64]) -> f64 {
+        M::sample(self, point)
+    }
+}
+
+/// Adapts a [`NoiseFn<f64, DIM>`](NoiseFn) to the [`DynSampler`] trait.
+#[derive(Clone, Copy, Debug)]
+pub struct DynNoiseFn<Source, const DIM: usize>
+where
+    Source: NoiseFn<f64, DIM>,
+{
+    pub source: Source,
+}
+
+impl<Source, const DIM: usize> DynNoiseFn<Source, DIM>
+where
+    Source: NoiseFn<f64, DIM>,
+{
+    pub fn new(source: Source) -> Self {
+        Self { source }
+    }
+}
+
+impl<Source, const DIM: usize> DynSampler for DynNoiseFn<Source, DIM>
+where
+    Source: NoiseFn<f64, DIM>,
+{
+    fn dim(&self) -> usize {
+        DIM
+    }
+
+    /// # Panics
+    ///
+    /// Panics if `point` does not have `DIM` coordinates.
+    fn sample(&self, point: &[f64]) -> f64 {
+        let point: [f64; DIM] = point
+            .try_into()
+            .unwrap_or_else(|_| panic!("expected {} coordinates, got {}", DIM, point.len()));
+
+        self.source.get(point)
+    }
+}
+
 /// Trait for functions that require a seed before generating their values
 pub trait Seedable {
     /// Set the seed for the function implementing the `Seedable` trait
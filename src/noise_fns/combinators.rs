@@ -0,0 +1,316 @@
+//! Lightweight adaptors for composing noise functions.
+//!
+//! Each adaptor wraps one or two [`NoiseFn<f64, DIM>`](NoiseFn) sources, applies simple
+//! arithmetic to their output, and implements `NoiseFn<f64, DIM>` itself, so adaptors nest
+//! freely and can be passed straight to a map builder. Unlike the combiners and modifiers
+//! exported from the crate root, they carry no extra type parameter for the point type, which
+//! lets [`ScaleBias`] also scale the input point.
+
+use crate::noise_fns::{NoiseFn, NoiseFnDeriv, NoiseFnF32};
+
+/// Noise function that outputs the sum of the output values from two source functions.
+#[derive(Clone, Debug)]
+pub struct Add<Source1, Source2, const DIM: usize>
+where
+    Source1: NoiseFn<f64, DIM>,
+    Source2: NoiseFn<f64, DIM>,
+{
+    /// Outputs a value.
+    pub source1: Source1,
+
+    /// Outputs a value.
+    pub source2: Source2,
+}
+
+impl<Source1, Source2, const DIM: usize> Add<Source1, Source2, DIM>
+where
+    Source1: NoiseFn<f64, DIM>,
+    Source2: NoiseFn<f64, DIM>,
+{
+    pub fn new(source1: Source1, source2: Source2) -> Self {
+        Self { source1, source2 }
+    }
+}
+
+impl<Source1, Source2, const DIM: usize> NoiseFn<f64, DIM> for Add<Source1, Source2, DIM>
+where
+    Source1: NoiseFn<f64, DIM>,
+    Source2: NoiseFn<f64, DIM>,
+{
+    fn get(&self, point: [f64; DIM]) -> f64 {
+        self.source1.get(point) + self.source2.get(point)
+    }
+}
+
+/// Noise function that outputs the product of the output values from two source functions.
+#[derive(Clone, Debug)]
+pub struct Multiply<Source1, Source2, const DIM: usize>
+where
+    Source1: NoiseFn<f64, DIM>,
+    Source2: NoiseFn<f64, DIM>,
+{
+    /// Outputs a value.
+    pub source1: Source1,
+
+    /// Outputs a value.
+    pub source2: Source2,
+}
+
+impl<Source1, Source2, const DIM: usize> Multiply<Source1, Source2, DIM>
+where
+    Source1: NoiseFn<f64, DIM>,
+    Source2: NoiseFn<f64, DIM>,
+{
+    pub fn new(source1: Source1, source2: Source2) -> Self {
+        Self { source1, source2 }
+    }
+}
+
+impl<Source1, Source2, const DIM: usize> NoiseFn<f64, DIM> for Multiply<Source1, Source2, DIM>
+where
+    Source1: NoiseFn<f64, DIM>,
+    Source2: NoiseFn<f64, DIM>,
+{
+    fn get(&self, point: [f64; DIM]) -> f64 {
+        self.source1.get(point) * self.source2.get(point)
+    }
+}
+
+/// Noise function that multiplies the output value from the source function by a factor.
+#[derive(Clone, Debug)]
+pub struct Scale<Source, const DIM: usize>
+where
+    Source: NoiseFn<f64, DIM>,
+{
+    /// Outputs a value.
+    pub source: Source,
+
+    /// Factor to multiply the output value by.
+    pub factor: f64,
+}
+
+impl<Source, const DIM: usize> Scale<Source, DIM>
+where
+    Source: NoiseFn<f64, DIM>,
+{
+    pub fn new(source: Source, factor: f64) -> Self {
+        Self { source, factor }
+    }
+}
+
+impl<Source, const DIM: usize> NoiseFn<f64, DIM> for Scale<Source, DIM>
+where
+    Source: NoiseFn<f64, DIM>,
+{
+    fn get(&self, point: [f64; DIM]) -> f64 {
+        self.source.get(point) * self.factor
+    }
+}
+
+/// Noise function that adds a constant offset to the output value from the source function.
+#[derive(Clone, Debug)]
+pub struct Bias<Source, const DIM: usize>
+where
+    Source: NoiseFn<f64, DIM>,
+{
+    /// Outputs a value.
+    pub source: Source,
+
+    /// Offset to add to the output value.
+    pub bias: f64,
+}
+
+impl<Source, const DIM: usize> Bias<Source, DIM>
+where
+    Source: NoiseFn<f64, DIM>,
+{
+    pub fn new(source: Source, bias: f64) -> Self {
+        Self { source, bias }
+    }
+}
+
+impl<Source, const DIM: usize> NoiseFn<f64, DIM> for Bias<Source, DIM>
+where
+    Source: NoiseFn<f64, DIM>,
+{
+    fn get(&self, point: [f64; DIM]) -> f64 {
+        self.source.get(point) + self.bias
+    }
+}
+
+/// Noise function that scales the input point, then scales and biases the output value from the
+/// source function, computing `source.get(point * input_scale) * scale + bias` in one step.
+#[derive(Clone, Debug)]
+pub struct ScaleBias<Source, const DIM: usize>
+where
+    Source: NoiseFn<f64, DIM>,
+{
+    /// Outputs a value.
+    pub source: Source,
+
+    /// Factor to multiply the input point by. The default value is 1.0.
+    pub input_scale: f64,
+
+    /// Factor to multiply the output value by. The default value is 1.0.
+    pub scale: f64,
+
+    /// Offset to add to the scaled output value. The default value is 0.0.
+    pub bias: f64,
+}
+
+impl<Source, const DIM: usize> ScaleBias<Source, DIM>
+where
+    Source: NoiseFn<f64, DIM>,
+{
+    pub fn new(source: Source) -> Self {
+        Self {
+            source,
+            input_scale: 1.0,
+            scale: 1.0,
+            bias: 0.0,
+        }
+    }
+
+    pub fn set_input_scale(self, input_scale: f64) -> Self {
+        Self {
+            input_scale,
+            ..self
+        }
+    }
+
+    pub fn set_scale(self, scale: f64) -> Self {
+        Self { scale, ..self }
+    }
+
+    pub fn set_bias(self, bias: f64) -> Self {
+        Self { bias, ..self }
+    }
+}
+
+impl<Source, const DIM: usize> NoiseFn<f64, DIM> for ScaleBias<Source, DIM>
+where
+    Source: NoiseFn<f64, DIM>,
+{
+    fn get(&self, point: [f64; DIM]) -> f64 {
+        let point = point.map(|coord| coord * self.input_scale);
+
+        self.source.get(point) * self.scale + self.bias
+    }
+}
+
+/// Noise function that clamps the output value from the source function to a range.
+#[derive(Clone, Debug)]
+pub struct Clamp<Source, const DIM: usize>
+where
+    Source: NoiseFn<f64, DIM>,
+{
+    /// Outputs a value.
+    pub source: Source,
+
+    /// Lower and upper bound of the output range.
+    pub bounds: (f64, f64),
+}
+
+impl<Source, const DIM: usize> Clamp<Source, DIM>
+where
+    Source: NoiseFn<f64, DIM>,
+{
+    /// # Panics
+    ///
+    /// Panics if `lower_bound` is greater than `upper_bound`.
+    pub fn new(source: Source, lower_bound: f64, upper_bound: f64) -> Self {
+        assert!(
+            lower_bound <= upper_bound,
+            "lower bound must not exceed upper bound"
+        );
+
+        Self {
+            source,
+            bounds: (lower_bound, upper_bound),
+        }
+    }
+}
+
+impl<Source, const DIM: usize> NoiseFn<f64, DIM> for Clamp<Source, DIM>
+where
+    Source: NoiseFn<f64, DIM>,
+{
+    fn get(&self, point: [f64; DIM]) -> f64 {
+        self.source.get(point).clamp(self.bounds.0, self.bounds.1)
+    }
+}
+
+/// Implements the extension traits with their default bodies for the adaptors in this module.
+macro_rules! impl_default_extensions {
+    ($($ty:ident<$($param:ident),*>),* $(,)?) => {$(
+        impl<$($param,)* const DIM: usize> NoiseFnDeriv<DIM> for $ty<$($param,)* DIM>
+        where
+            $($param: NoiseFn<f64, DIM>,)*
+        {
+        }
+
+        impl<$($param,)* const DIM: usize> NoiseFnF32<DIM> for $ty<$($param,)* DIM>
+        where
+            $($param: NoiseFn<f64, DIM>,)*
+        {
+        }
+    )*};
+}
+
+impl_default_extensions!(
+    Add<Source1, Source2>,
+    Multiply<Source1, Source2>,
+    Scale<Source>,
+    Bias<Source>,
+    ScaleBias<Source>,
+    Clamp<Source>,
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        utils::{NoiseMapBuilder, PlaneMapBuilder},
+        Constant, Perlin, Value,
+    };
+
+    #[test]
+    fn adaptors_compose_two_sources() {
+        let perlin = Perlin::new(7);
+        let value = Value::new(3);
+        let point = [0.4, -1.3, 2.6];
+        let (a, b) = (perlin.get(point), value.get(point));
+
+        assert_eq!(a + b, Add::new(perlin, value).get(point));
+        assert_eq!(a * b, Multiply::new(perlin, value).get(point));
+
+        let composed = Clamp::new(
+            Bias::new(Scale::new(Add::new(perlin, value), 4.0), 0.5),
+            -1.0,
+            1.0,
+        );
+        assert_eq!(((a + b) * 4.0 + 0.5).clamp(-1.0, 1.0), composed.get(point));
+    }
+
+    #[test]
+    fn scale_bias_transforms_input_and_output() {
+        let perlin = Perlin::new(11);
+        let scale_bias = ScaleBias::new(perlin)
+            .set_input_scale(2.5)
+            .set_scale(3.0)
+            .set_bias(-0.5);
+
+        let point = [0.3, -1.7, 4.2];
+        let expected = perlin.get([0.75, -4.25, 10.5]) * 3.0 - 0.5;
+
+        assert!((scale_bias.get(point) - expected).abs() < 1e-12);
+    }
+
+    #[test]
+    fn adaptors_work_as_builder_sources() {
+        let source = Bias::new(Scale::new(Constant::new(0.25), 2.0), -0.125);
+        let map = PlaneMapBuilder::new(source).set_size(4, 4).build();
+
+        assert!(map.iter().all(|&value| value == 0.375));
+    }
+}
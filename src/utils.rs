@@ -1,10 +1,16 @@
 #[cfg(feature = "image")]
 pub use self::image_renderer::*;
-pub use self::{color_gradient::*, noise_image::*, noise_map::*, noise_map_builder::*};
+pub use self::{
+    bounded_noise_map::*, color_gradient::*, map_pipeline::*, noise_image::*, noise_map::*,
+    noise_map_builder::*, noise_map_source::*,
+};
 
+mod bounded_noise_map;
 mod color_gradient;
 #[cfg(feature = "image")]
 mod image_renderer;
+mod map_pipeline;
 mod noise_image;
 mod noise_map;
 mod noise_map_builder;
+mod noise_map_source;
@@ -0,0 +1,128 @@
+use crate::utils::NoiseMap;
+
+/// A [`NoiseMap`] together with the world-space bounds it was built over.
+///
+/// Cells are laid out the same way [`PlaneMapBuilder`](crate::utils::PlaneMapBuilder) samples
+/// them: cell `(x, y)` lies at `(x_bounds.0 + x * x_extent / width, y_bounds.0 + y * y_extent /
+/// height)`.
+#[derive(Clone)]
+pub struct BoundedNoiseMap {
+    map: NoiseMap,
+    x_bounds: (f64, f64),
+    y_bounds: (f64, f64),
+}
+
+impl BoundedNoiseMap {
+    pub fn new(map: NoiseMap, x_bounds: (f64, f64), y_bounds: (f64, f64)) -> Self {
+        Self {
+            map,
+            x_bounds,
+            y_bounds,
+        }
+    }
+
+    pub fn map(&self) -> &NoiseMap {
+        &self.map
+    }
+
+    pub fn into_map(self) -> NoiseMap {
+        self.map
+    }
+
+    pub fn x_bounds(&self) -> (f64, f64) {
+        self.x_bounds
+    }
+
+    pub fn y_bounds(&self) -> (f64, f64) {
+        self.y_bounds
+    }
+
+    /// Converts a world-space position to fractional cell coordinates.
+    pub fn world_to_cell(&self, world_x: f64, world_y: f64) -> (f64, f64) {
+        let (width, height) = self.map.size();
+
+        (
+            (world_x - self.x_bounds.0) / (self.x_bounds.1 - self.x_bounds.0) * width as f64,
+            (world_y - self.y_bounds.0) / (self.y_bounds.1 - self.y_bounds.0) * height as f64,
+        )
+    }
+
+    /// Returns the bilinearly interpolated value at a world-space position. Positions outside
+    /// the map are clamped to its edges.
+    pub fn value_at(&self, world_x: f64, world_y: f64) -> f64 {
+        let (x, y) = self.world_to_cell(world_x, world_y);
+
+        self.map.sample_bilinear(x, y)
+    }
+
+    /// Iterates over the map in row-major order, yielding the world-space position of each cell
+    /// together with its value as `(world_x, world_y, value)`.
+    pub fn iter_world(&self) -> impl Iterator<Item = (f64, f64, f64)> + '_ {
+        let (width, height) = self.map.size();
+        let x_step = (self.x_bounds.1 - self.x_bounds.0) / width as f64;
+        let y_step = (self.y_bounds.1 - self.y_bounds.0) / height as f64;
+
+        (0..height).flat_map(move |y| {
+            let world_y = self.y_bounds.0 + y_step * y as f64;
+
+            (0..width).map(move |x| {
+                (
+                    self.x_bounds.0 + x_step * x as f64,
+                    world_y,
+                    self.map[(x, y)],
+                )
+            })
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        utils::{NoiseMapBuilder, PlaneMapBuilder},
+        Perlin,
+    };
+
+    #[test]
+    fn value_at_corners() {
+        let bounded = PlaneMapBuilder::new(Perlin::new(9))
+            .set_size(12, 9)
+            .set_x_bounds(-2.0, 2.0)
+            .set_y_bounds(1.0, 5.0)
+            .build_bounded();
+        let map = bounded.map();
+
+        assert_eq!((-2.0, 2.0), bounded.x_bounds());
+        assert_eq!((1.0, 5.0), bounded.y_bounds());
+        assert_eq!(map[(0, 0)], bounded.value_at(-2.0, 1.0));
+        assert_eq!(map[(11, 8)], bounded.value_at(2.0, 5.0));
+        assert_eq!(map[(11, 0)], bounded.value_at(4.0, -3.0));
+    }
+
+    #[test]
+    fn seamless_build_bounded_matches_cells() {
+        let builder = PlaneMapBuilder::new(Perlin::new(9))
+            .set_size(12, 9)
+            .set_is_seamless(true);
+        let bounded = builder.build_bounded();
+        let (x_map, y_map) = builder.build_coords();
+
+        let value = bounded.value_at(x_map[(5, 4)], y_map[(5, 4)]);
+        assert!((value - bounded.map()[(5, 4)]).abs() < 1e-12);
+    }
+
+    #[test]
+    fn iter_world_spans_bounds() {
+        let bounded = PlaneMapBuilder::new(Perlin::new(9))
+            .set_size(8, 4)
+            .set_x_bounds(-2.0, 2.0)
+            .set_y_bounds(1.0, 5.0)
+            .build_bounded();
+        let cells: alloc::vec::Vec<_> = bounded.iter_world().collect();
+
+        assert_eq!(32, cells.len());
+        assert_eq!((-2.0, 1.0, bounded.map()[(0, 0)]), cells[0]);
+        assert_eq!((1.5, 4.0, bounded.map()[(7, 3)]), cells[31]);
+        assert_eq!((-1.5, 1.0), (cells[1].0, cells[1].1));
+    }
+}
@@ -0,0 +1,120 @@
+use crate::{utils::NoiseMap, NoiseFn, Terrace};
+use alloc::{boxed::Box, vec::Vec};
+
+/// An ordered list of post-processing steps applied to a [`NoiseMap`].
+///
+/// Steps run in the order they were added, each receiving the output of the previous one.
+#[derive(Default)]
+pub struct MapPipeline {
+    steps: Vec<Box<dyn Fn(NoiseMap) -> NoiseMap>>,
+}
+
+impl MapPipeline {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Appends `step` to the end of the pipeline.
+    pub fn then<F>(mut self, step: F) -> Self
+    where
+        F: Fn(NoiseMap) -> NoiseMap + 'static,
+    {
+        self.steps.push(Box::new(step));
+        self
+    }
+
+    pub fn len(&self) -> usize {
+        self.steps.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.steps.is_empty()
+    }
+
+    /// Runs every step of the pipeline on `map` and returns the result.
+    pub fn run(&self, map: NoiseMap) -> NoiseMap {
+        self.steps.iter().fold(map, |map, step| step(map))
+    }
+}
+
+/// Returns a step that linearly rescales the finite values of a map to `[-1, 1]`.
+///
+/// Maps whose finite values are all equal become 0.0, and non-finite values are left unchanged.
+pub fn normalize_step() -> impl Fn(NoiseMap) -> NoiseMap {
+    |mut map: NoiseMap| {
+        let (min, max) = map
+            .iter()
+            .filter(|value| value.is_finite())
+            .fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), &value| {
+                (min.min(value), max.max(value))
+            });
+        let range = max - min;
+
+        for value in map.iter_mut().filter(|value| value.is_finite()) {
+            *value = if range > 0.0 {
+                (*value - min) / range * 2.0 - 1.0
+            } else {
+                0.0
+            };
+        }
+
+        map
+    }
+}
+
+/// Returns a step that applies the terrace-forming curve of [`Terrace`] with the given control
+/// points to every value.
+///
+/// # Panics
+///
+/// The step panics if fewer than two distinct control points are given.
+pub fn terrace_step(points: &[f64]) -> impl Fn(NoiseMap) -> NoiseMap {
+    let terrace = points
+        .iter()
+        .fold(Terrace::new(Identity), |terrace, &point| {
+            terrace.add_control_point(point)
+        });
+
+    move |map: NoiseMap| map.map(|value| terrace.get([value]))
+}
+
+/// Returns a step that applies [`NoiseMap::box_blur`] with the given radius.
+pub fn blur_step(radius: usize) -> impl Fn(NoiseMap) -> NoiseMap {
+    move |map: NoiseMap| map.box_blur(radius)
+}
+
+/// Passes its one-dimensional input through unchanged, so that noise function modifiers can be
+/// applied to plain values.
+struct Identity;
+
+impl NoiseFn<f64, 1> for Identity {
+    fn get(&self, point: [f64; 1]) -> f64 {
+        point[0]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pipeline_matches_manual_steps() {
+        let map = NoiseMap::from_fn(8, 6, |x, y| (x * y) as f64 * 0.1 - 1.5);
+
+        let pipeline = MapPipeline::new()
+            .then(normalize_step())
+            .then(terrace_step(&[-1.0, 0.0, 1.0]));
+        assert_eq!(2, pipeline.len());
+
+        let manual = terrace_step(&[-1.0, 0.0, 1.0])(normalize_step()(map.clone()));
+        let piped = pipeline.run(map.clone());
+        assert!(manual.iter().eq(piped.iter()));
+
+        let normalized = normalize_step()(map);
+        assert_eq!(-1.0, normalized[(0, 0)]);
+        assert_eq!(1.0, normalized[(7, 5)]);
+        assert!(blur_step(1)(normalized.clone())
+            .iter()
+            .eq(normalized.box_blur(1).iter()));
+    }
+}
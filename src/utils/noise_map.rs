@@ -1,14 +1,106 @@
+use crate::math::{interpolate, s_curve::cubic::Cubic};
 use alloc::{
     slice::{Iter, IterMut},
     vec::{IntoIter, Vec},
 };
-use core::ops::{Index, IndexMut};
-#[cfg(feature = "images")]
-use std::path::Path;
+use core::{
+    fmt,
+    ops::{Index, IndexMut},
+};
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+#[cfg(feature = "std")]
+use std::{fs::File, io, io::Write, path::Path};
 
 const RASTER_MAX_WIDTH: u16 = 32_767;
 const RASTER_MAX_HEIGHT: u16 = 32_767;
 
+#[cfg(feature = "std")]
+const RLE_MAGIC: &[u8; 4] = b"NMRL";
+
+/// Errors returned by operations that combine several noise maps.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NoiseMapError {
+    /// No input maps were provided.
+    EmptyInput,
+    /// An input map's size differs from the size of the first map.
+    SizeMismatch {
+        expected: (usize, usize),
+        found: (usize, usize),
+    },
+}
+
+impl fmt::Display for NoiseMapError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::EmptyInput => write!(f, "no noise maps were provided"),
+            Self::SizeMismatch { expected, found } => write!(
+                f,
+                "noise map size {:?} does not match expected size {:?}",
+                found, expected
+            ),
+        }
+    }
+}
+
+/// Photoshop-style blend modes used by [`NoiseMap::blend_mode`].
+///
+/// Each mode combines a base value `a` and a blend value `b`, both normalized to `[0, 1]`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BlendMode {
+    /// `a * b`
+    Multiply,
+    /// `1 - (1 - a) * (1 - b)`
+    Screen,
+    /// Multiply where the base is dark, screen where it is light.
+    Overlay,
+    /// A gentler overlay, following the W3C compositing specification.
+    SoftLight,
+    /// `|a - b|`
+    Difference,
+}
+
+impl BlendMode {
+    fn apply(self, a: f64, b: f64) -> f64 {
+        match self {
+            Self::Multiply => a * b,
+            Self::Screen => 1.0 - (1.0 - a) * (1.0 - b),
+            Self::Overlay => {
+                if a < 0.5 {
+                    2.0 * a * b
+                } else {
+                    1.0 - 2.0 * (1.0 - a) * (1.0 - b)
+                }
+            }
+            Self::SoftLight => {
+                if b <= 0.5 {
+                    a - (1.0 - 2.0 * b) * a * (1.0 - a)
+                } else {
+                    let d = if a <= 0.25 {
+                        ((16.0 * a - 12.0) * a + 4.0) * a
+                    } else {
+                        a.sqrt()
+                    };
+
+                    a + (2.0 * b - 1.0) * (d - a)
+                }
+            }
+            Self::Difference => (a - b).abs(),
+        }
+    }
+}
+
+/// Which neighbors of a cell count as connected to it, used by [`NoiseMap::count_islands_with`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Connectivity {
+    /// Cells connect to the four cells sharing an edge with them.
+    #[default]
+    Four,
+    /// Cells also connect diagonally, to all eight surrounding cells.
+    Eight,
+}
+
+#[derive(Clone)]
 pub struct NoiseMap {
     size: (usize, usize),
     border_value: f64,
@@ -20,14 +112,236 @@ impl NoiseMap {
         Self::initialize().set_size(width, height)
     }
 
+    /// Creates a map by evaluating `f(x, y)` for every cell.
+    pub fn from_fn<F>(width: usize, height: usize, f: F) -> Self
+    where
+        F: Fn(usize, usize) -> f64,
+    {
+        let mut result = Self::new(width, height);
+
+        for y in 0..height {
+            for x in 0..width {
+                result[(x, y)] = f(x, y);
+            }
+        }
+
+        result
+    }
+
+    /// Parallel version of [`from_fn`](Self::from_fn), producing an identical map.
+    #[cfg(feature = "rayon")]
+    pub fn from_fn_par<F>(width: usize, height: usize, f: F) -> Self
+    where
+        F: Fn(usize, usize) -> f64 + Sync,
+    {
+        let mut result = Self::new(width, height);
+
+        result
+            .map
+            .par_iter_mut()
+            .take(width * height)
+            .enumerate()
+            .for_each(|(i, value)| *value = f(i % width, i / width));
+
+        result
+    }
+
+    /// Calls `f(y, row)` for every row of the map in parallel, where `row` is the mutable slice
+    /// of row `y`'s values.
+    #[cfg(feature = "rayon")]
+    pub fn par_rows_mut<F>(&mut self, f: F)
+    where
+        F: Fn(usize, &mut [f64]) + Sync,
+    {
+        let width = self.size.0.max(1);
+
+        self.as_mut_slice()
+            .par_chunks_mut(width)
+            .enumerate()
+            .for_each(|(y, row)| f(y, row));
+    }
+
     pub fn iter(&self) -> Iter<'_, f64> {
         self.map.iter()
     }
 
+    /// Returns an iterator over `(x, y, value)` for every cell, in Morton (Z-order) order.
+    ///
+    /// Maps that are not square with a power-of-two side are traversed along the Morton curve of
+    /// the smallest enclosing power-of-two square, skipping the cells that lie outside the map.
+    pub fn iter_morton(&self) -> impl Iterator<Item = (usize, usize, f64)> + '_ {
+        let (width, height) = self.size;
+        let side = width.max(height).next_power_of_two();
+        let cells = if width * height == 0 { 0 } else { side * side };
+
+        (0..cells)
+            .map(|code| (compact_bits(code), compact_bits(code >> 1)))
+            .filter(move |&(x, y)| x < width && y < height)
+            .map(move |(x, y)| (x, y, self[(x, y)]))
+    }
+
     pub fn iter_mut(&mut self) -> IterMut<'_, f64> {
         self.map.iter_mut()
     }
 
+    /// Returns the values of the map as a contiguous row-major slice, without copying.
+    pub fn as_slice(&self) -> &[f64] {
+        let (width, height) = self.size;
+
+        &self.map[..width * height]
+    }
+
+    /// Returns the values of the map in row-major order, converted to `f32`.
+    pub fn to_f32_vec(&self) -> Vec<f32> {
+        self.as_slice().iter().map(|&value| value as f32).collect()
+    }
+
+    /// Returns the width, height and row-major values of the map as a single-channel `f32`
+    /// texture, with values remapped from `[-1, 1]` to `[0, 1]` and clamped.
+    pub fn to_texture_data(&self) -> (u32, u32, Vec<f32>) {
+        let (width, height) = self.size;
+        let texels = self
+            .as_slice()
+            .iter()
+            .map(|&value| (value * 0.5 + 0.5).clamp(0.0, 1.0) as f32)
+            .collect();
+
+        (width as u32, height as u32, texels)
+    }
+
+    /// Returns the map as an indexed triangle mesh for direct upload to a GPU, as vertex
+    /// positions and triangle indices.
+    ///
+    /// There is one vertex per cell, in row-major order, at `[x, value * height_scale, y]`, so
+    /// the height runs along the y axis. Each grid square is split into two triangles that share
+    /// the vertices of their neighbors, wound counter-clockwise when viewed from above.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the map has more cells than a `u32` index can address.
+    pub fn to_mesh(&self, height_scale: f64) -> (Vec<[f32; 3]>, Vec<u32>) {
+        let (width, height) = self.size;
+        assert!(
+            width * height <= u32::MAX as usize + 1,
+            "noise map is too large to index with u32"
+        );
+
+        let positions = self
+            .as_slice()
+            .iter()
+            .enumerate()
+            .map(|(i, &value)| {
+                [
+                    (i % width) as f32,
+                    (value * height_scale) as f32,
+                    (i / width) as f32,
+                ]
+            })
+            .collect();
+
+        let mut indices =
+            Vec::with_capacity(width.saturating_sub(1) * height.saturating_sub(1) * 6);
+        for y in 1..height {
+            for x in 1..width {
+                let top_left = ((y - 1) * width + x - 1) as u32;
+                let top_right = top_left + 1;
+                let bottom_left = top_left + width as u32;
+                let bottom_right = bottom_left + 1;
+
+                indices.extend_from_slice(&[
+                    top_left,
+                    bottom_left,
+                    top_right,
+                    top_right,
+                    bottom_left,
+                    bottom_right,
+                ]);
+            }
+        }
+
+        (positions, indices)
+    }
+
+    /// Returns the map as row-major 8-bit grayscale, with the `low_pct` and `high_pct`
+    /// percentiles of its values stretched to 0 and 255.
+    ///
+    /// Percentiles are given in `0.0..=100.0` and interpolated linearly between the sorted
+    /// values, ignoring NaN. Values beyond them clamp to 0 or 255, so a few outliers cannot
+    /// compress the contrast of the rest of the map. NaN cells, and every cell of a map whose
+    /// percentiles coincide, become 0.
+    pub fn to_grayscale_autocontrast(&self, low_pct: f64, high_pct: f64) -> Vec<u8> {
+        let mut sorted: Vec<f64> = self
+            .as_slice()
+            .iter()
+            .copied()
+            .filter(|value| !value.is_nan())
+            .collect();
+        sorted.sort_by(f64::total_cmp);
+
+        let percentile = |pct: f64| {
+            let rank = (pct / 100.0).clamp(0.0, 1.0) * sorted.len().saturating_sub(1) as f64;
+            let (lower, upper) = (rank.floor() as usize, rank.ceil() as usize);
+
+            interpolate::linear(sorted[lower], sorted[upper], rank - lower as f64)
+        };
+        let (low, high) = if sorted.is_empty() {
+            (0.0, 0.0)
+        } else {
+            (percentile(low_pct), percentile(high_pct))
+        };
+
+        self.as_slice()
+            .iter()
+            .map(|&value| {
+                let t = (value - low) / (high - low);
+
+                if t.is_nan() {
+                    0
+                } else {
+                    (t.clamp(0.0, 1.0) * 255.0) as u8
+                }
+            })
+            .collect()
+    }
+
+    /// Returns, for every cell in row-major order, the index of the nearest value in
+    /// `palette_values`.
+    ///
+    /// Ties go to the entry listed first, and NaN cells map to index 0.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `palette_values` is empty or has more than 256 entries.
+    pub fn to_palette_indices(&self, palette_values: &[f64]) -> Vec<u8> {
+        assert!(
+            !palette_values.is_empty() && palette_values.len() <= 256,
+            "palette must have between 1 and 256 entries"
+        );
+
+        self.as_slice()
+            .iter()
+            .map(|&value| {
+                let mut nearest = (0, (palette_values[0] - value).abs());
+
+                for (index, entry) in palette_values.iter().enumerate().skip(1) {
+                    let distance = (entry - value).abs();
+
+                    if distance < nearest.1 {
+                        nearest = (index, distance);
+                    }
+                }
+
+                nearest.0 as u8
+            })
+            .collect()
+    }
+
+    pub(crate) fn as_mut_slice(&mut self) -> &mut [f64] {
+        let (width, height) = self.size;
+
+        &mut self.map[..width * height]
+    }
+
     pub fn set_size(self, width: usize, height: usize) -> Self {
         // Check for invalid width or height.
         assert!(width < RASTER_MAX_WIDTH as usize);
@@ -93,95 +407,2609 @@ impl NoiseMap {
         }
     }
 
-    #[cfg(feature = "images")]
-    pub fn write_to_file(&self, filename: &Path) {
-        // collect the values from f64 into u8 in a separate vec
+    /// Samples the map at fractional cell coordinates using bilinear interpolation.
+    ///
+    /// Integer coordinates return the corresponding cell value exactly. Coordinates outside the
+    /// map are clamped to the nearest edge. Empty maps return the border value.
+    pub fn sample_bilinear(&self, x: f64, y: f64) -> f64 {
         let (width, height) = self.size;
-        let mut pixels: Vec<u8> = Vec::with_capacity(width * height);
 
-        for i in &self.map {
-            pixels.push(((i * 0.5 + 0.5).clamp(0.0, 1.0) * 255.0) as u8);
+        if width == 0 || height == 0 {
+            return self.border_value;
         }
 
-        let _ = image::save_buffer(
-            filename,
-            &pixels,
-            self.size.0 as u32,
-            self.size.1 as u32,
-            image::ColorType::L8,
-        );
+        let x = x.clamp(0.0, (width - 1) as f64);
+        let y = y.clamp(0.0, (height - 1) as f64);
 
-        println!("\nFinished generating {}", filename.to_string_lossy());
+        let (x0, y0) = (x.floor() as usize, y.floor() as usize);
+        let (x1, y1) = ((x0 + 1).min(width - 1), (y0 + 1).min(height - 1));
+        let (x_alpha, y_alpha) = (x - x0 as f64, y - y0 as f64);
+
+        let top = interpolate::linear(self[(x0, y0)], self[(x1, y0)], x_alpha);
+        let bottom = interpolate::linear(self[(x0, y1)], self[(x1, y1)], x_alpha);
+
+        interpolate::linear(top, bottom, y_alpha)
     }
 
-    fn initialize() -> Self {
-        Self {
-            size: (0, 0),
-            border_value: 0.0,
-            map: Vec::new(),
+    /// Returns a new map with `f` applied to every value.
+    pub fn map<F>(&self, f: F) -> Self
+    where
+        F: Fn(f64) -> f64,
+    {
+        let (width, height) = self.size;
+
+        Self::from_fn(width, height, |x, y| f(self[(x, y)])).set_border_value(self.border_value)
+    }
+
+    /// Returns a new map with every value negated, turning peaks into valleys.
+    pub fn inverted(&self) -> Self {
+        self.map(|value| -value)
+    }
+
+    /// Negates every value of the map in place.
+    pub fn invert(&mut self) {
+        self.iter_mut().for_each(|value| *value = -*value);
+    }
+
+    /// Returns a new map with gamma correction applied to every value.
+    ///
+    /// Values are mapped from `[-1, 1]` to `[0, 1]`, raised to the power `1 / gamma`, and mapped
+    /// back. Values outside `[-1, 1]` are clamped first. A `gamma` above 1.0 brightens the
+    /// midtones, which suits exports viewed on typical displays.
+    pub fn gamma(&self, gamma: f64) -> Self {
+        self.map(|value| {
+            let normalized = (value * 0.5 + 0.5).clamp(0.0, 1.0);
+
+            normalized.powf(1.0 / gamma) * 2.0 - 1.0
+        })
+    }
+
+    /// Returns a new map with every value remapped through a monotone cubic spline passing
+    /// through the `(input, output)` control points.
+    ///
+    /// The points may be given in any order and are sorted by input; points repeating an
+    /// earlier input are ignored. Between control points the spline is a piecewise cubic
+    /// Hermite curve with Fritsch-Butland tangents, so it never overshoots monotone control
+    /// points. Values outside the input range clamp to the outputs of the first and last
+    /// points, and NaN values stay NaN. An empty slice returns an unchanged copy.
+    pub fn curve(&self, points: &[(f64, f64)]) -> Self {
+        let mut points = points.to_vec();
+        points.sort_by(|a, b| a.0.total_cmp(&b.0));
+        points.dedup_by(|later, earlier| later.0 == earlier.0);
+
+        let (first, last) = match (points.first(), points.last()) {
+            (Some(&first), Some(&last)) => (first, last),
+            _ => return self.clone(),
+        };
+
+        let secants: Vec<f64> = points
+            .windows(2)
+            .map(|pair| (pair[1].1 - pair[0].1) / (pair[1].0 - pair[0].0))
+            .collect();
+        let mut tangents = vec![0.0; points.len()];
+        for k in 1..points.len().saturating_sub(1) {
+            let (before, after) = (secants[k - 1], secants[k]);
+
+            if before * after > 0.0 {
+                let h_before = points[k].0 - points[k - 1].0;
+                let h_after = points[k + 1].0 - points[k].0;
+                let w_before = 2.0 * h_after + h_before;
+                let w_after = h_after + 2.0 * h_before;
+
+                tangents[k] = (w_before + w_after) / (w_before / before + w_after / after);
+            }
         }
+        if let (Some(&head), Some(&tail)) = (secants.first(), secants.last()) {
+            tangents[0] = head;
+            tangents[points.len() - 1] = tail;
+        }
+
+        self.map(|value| {
+            if value.is_nan() {
+                return value;
+            }
+            if value <= first.0 {
+                return first.1;
+            }
+            if value >= last.0 {
+                return last.1;
+            }
+
+            let k = points.partition_point(|point| point.0 <= value) - 1;
+            let ((x0, y0), (x1, y1)) = (points[k], points[k + 1]);
+            let h = x1 - x0;
+            let t = (value - x0) / h;
+
+            let h00 = (1.0 + 2.0 * t) * (1.0 - t) * (1.0 - t);
+            let h10 = t * (1.0 - t) * (1.0 - t);
+            let h01 = t * t * (3.0 - 2.0 * t);
+            let h11 = t * t * (t - 1.0);
+
+            h00 * y0 + h10 * h * tangents[k] + h01 * y1 + h11 * h * tangents[k + 1]
+        })
     }
-}
 
-impl Default for NoiseMap {
-    fn default() -> Self {
-        Self::initialize()
+    /// Returns a new map where values below `level` are replaced with `fill`, and all other
+    /// values, including NaN, are kept.
+    pub fn mask_below(&self, level: f64, fill: f64) -> Self {
+        self.map(|value| if value < level { fill } else { value })
     }
-}
 
-impl Index<(usize, usize)> for NoiseMap {
-    type Output = f64;
+    /// Returns a new map where values above `level` are replaced with `fill`, and all other
+    /// values, including NaN, are kept.
+    pub fn mask_above(&self, level: f64, fill: f64) -> Self {
+        self.map(|value| if value > level { fill } else { value })
+    }
 
-    fn index(&self, (x, y): (usize, usize)) -> &Self::Output {
+    /// Returns a new map with a GLSL-style smoothstep applied to every value.
+    ///
+    /// Values below `edge0` become 0.0, values above `edge1` become 1.0, and values in between
+    /// follow the cubic S-curve.
+    pub fn smooth_threshold(&self, edge0: f64, edge1: f64) -> Self {
         let (width, height) = self.size;
-        if x < width && y < height {
-            &self.map[x + y * width]
-        } else {
-            &self.border_value
+        let mut result = Self::new(width, height).set_border_value(self.border_value);
+
+        for y in 0..height {
+            for x in 0..width {
+                let t = (self[(x, y)] - edge0) / (edge1 - edge0);
+
+                result[(x, y)] = t.map_cubic();
+            }
         }
+
+        result
     }
-}
 
-impl IndexMut<(usize, usize)> for NoiseMap {
-    fn index_mut(&mut self, (x, y): (usize, usize)) -> &mut Self::Output {
+    /// Returns a new map of the given size filled by repeating this map, wrapping around at its
+    /// edges.
+    pub fn tiled(&self, out_width: usize, out_height: usize) -> Self {
         let (width, height) = self.size;
-        if x < width && y < height {
-            &mut self.map[x + y * width]
+        let mut result = Self::new(out_width, out_height).set_border_value(self.border_value);
+
+        if width == 0 || height == 0 {
+            return result;
+        }
+
+        for y in 0..out_height {
+            for x in 0..out_width {
+                result[(x, y)] = self[(x % width, y % height)];
+            }
+        }
+
+        result
+    }
+
+    /// Returns a new map holding the slope magnitude `sqrt(dx^2 + dy^2)` of every cell.
+    ///
+    /// Derivatives are estimated with central differences, falling back to one-sided
+    /// differences at the edges of the map.
+    pub fn slope(&self) -> Self {
+        let (width, height) = self.size;
+        let mut result = Self::new(width, height).set_border_value(self.border_value);
+
+        for y in 0..height {
+            for x in 0..width {
+                let (dx, dy) = self.gradient(x, y);
+
+                result[(x, y)] = (dx * dx + dy * dy).sqrt();
+            }
+        }
+
+        result
+    }
+
+    /// Returns a new map of shaded relief, lit from `azimuth_deg` degrees clockwise from north
+    /// (the `-y` direction) and `altitude_deg` degrees above the horizon.
+    ///
+    /// Each cell is the cosine of the angle between its surface normal and the light direction,
+    /// clamped to `[0, 1]`, so flat cells are `sin(altitude)`. The surface is the map's values
+    /// scaled by `z_factor` over a grid of unit cell spacing, with derivatives estimated as in
+    /// [`slope`](Self::slope).
+    pub fn hillshade(&self, azimuth_deg: f64, altitude_deg: f64, z_factor: f64) -> Self {
+        let (width, height) = self.size;
+        let (azimuth, altitude) = (azimuth_deg.to_radians(), altitude_deg.to_radians());
+        let light = [
+            altitude.cos() * azimuth.sin(),
+            -altitude.cos() * azimuth.cos(),
+            altitude.sin(),
+        ];
+
+        Self::from_fn(width, height, |x, y| {
+            let (dx, dy) = self.gradient(x, y);
+            let normal = [-z_factor * dx, -z_factor * dy, 1.0];
+            let length = (normal[0] * normal[0] + normal[1] * normal[1] + 1.0).sqrt();
+
+            ((normal[0] * light[0] + normal[1] * light[1] + normal[2] * light[2]) / length)
+                .clamp(0.0, 1.0)
+        })
+        .set_border_value(self.border_value)
+    }
+
+    /// Estimates the derivatives of the map along x and y at a cell with central differences,
+    /// falling back to one-sided differences at the edges of the map.
+    fn gradient(&self, x: usize, y: usize) -> (f64, f64) {
+        let (width, height) = self.size;
+        let (x0, x1) = (x.saturating_sub(1), (x + 1).min(width - 1));
+        let (y0, y1) = (y.saturating_sub(1), (y + 1).min(height - 1));
+
+        let dx = if x1 > x0 {
+            (self[(x1, y)] - self[(x0, y)]) / (x1 - x0) as f64
         } else {
-            panic!(
-                "index ({}, {}) out of bounds for NoiseMap of size ({}, {})",
-                x, y, width, height
-            )
+            0.0
+        };
+        let dy = if y1 > y0 {
+            (self[(x, y1)] - self[(x, y0)]) / (y1 - y0) as f64
+        } else {
+            0.0
+        };
+
+        (dx, dy)
+    }
+
+    /// Returns a new map holding the discrete Laplacian of every cell, using the 5-point stencil
+    /// `left + right + up + down - 4 * center`.
+    ///
+    /// Positive values mark cells lower than their neighbors (pits and valleys), and negative
+    /// values mark cells higher than their neighbors (bumps and ridges). Neighbors outside the
+    /// map are clamped to the nearest edge cell.
+    pub fn laplacian(&self) -> Self {
+        let (width, height) = self.size;
+        let mut result = Self::new(width, height).set_border_value(self.border_value);
+
+        for y in 0..height {
+            let (y0, y1) = (y.saturating_sub(1), (y + 1).min(height - 1));
+
+            for x in 0..width {
+                let (x0, x1) = (x.saturating_sub(1), (x + 1).min(width - 1));
+
+                result[(x, y)] = self[(x0, y)] + self[(x1, y)] + self[(x, y0)] + self[(x, y1)]
+                    - 4.0 * self[(x, y)];
+            }
         }
+
+        result
     }
-}
 
-impl IntoIterator for NoiseMap {
-    type Item = f64;
+    /// Returns the coordinates, in row-major order, of cells whose value differs from one of
+    /// their 4-connected neighbors by more than `threshold`.
+    ///
+    /// A NaN cell next to a non-NaN cell counts as a discontinuity on both sides, so NaN
+    /// boundaries are flagged while the interior of a NaN region is not.
+    pub fn discontinuities(&self, threshold: f64) -> Vec<(usize, usize)> {
+        let (width, height) = self.size;
+        let is_jump = |a: f64, b: f64| a.is_nan() != b.is_nan() || (a - b).abs() > threshold;
 
-    type IntoIter = IntoIter<f64>;
+        let mut flagged = Vec::new();
 
-    fn into_iter(self) -> Self::IntoIter {
-        self.map.into_iter()
+        for y in 0..height {
+            for x in 0..width {
+                let value = self[(x, y)];
+                let neighbors = [
+                    (x > 0).then(|| (x - 1, y)),
+                    (x + 1 < width).then(|| (x + 1, y)),
+                    (y > 0).then(|| (x, y - 1)),
+                    (y + 1 < height).then(|| (x, y + 1)),
+                ];
+
+                if neighbors
+                    .iter()
+                    .flatten()
+                    .any(|&neighbor| is_jump(value, self[neighbor]))
+                {
+                    flagged.push((x, y));
+                }
+            }
+        }
+
+        flagged
     }
-}
 
-impl<'a> IntoIterator for &'a NoiseMap {
-    type Item = &'a f64;
+    /// Returns the coordinates of the largest value in the map, ignoring NaN.
+    ///
+    /// Ties are resolved in favor of the first cell in row-major order. Returns `None` if the map
+    /// is empty or holds only NaN.
+    pub fn argmax(&self) -> Option<(usize, usize)> {
+        self.find_extremum(|candidate, best| candidate > best)
+    }
 
-    type IntoIter = Iter<'a, f64>;
+    /// Returns the coordinates of the smallest value in the map, ignoring NaN.
+    ///
+    /// Ties are resolved in favor of the first cell in row-major order. Returns `None` if the map
+    /// is empty or holds only NaN.
+    pub fn argmin(&self) -> Option<(usize, usize)> {
+        self.find_extremum(|candidate, best| candidate < best)
+    }
 
-    fn into_iter(self) -> Self::IntoIter {
-        self.iter()
+    /// Returns the smallest and largest values in the `w` by `h` region whose top-left cell is
+    /// `(x, y)`, ignoring NaN.
+    ///
+    /// Returns `None` if the region extends past the map, is empty, or holds only NaN.
+    pub fn region_minmax(&self, x: usize, y: usize, w: usize, h: usize) -> Option<(f64, f64)> {
+        let (width, height) = self.size;
+        let x_end = x.checked_add(w).filter(|&end| end <= width)?;
+        let y_end = y.checked_add(h).filter(|&end| end <= height)?;
+
+        (y..y_end)
+            .flat_map(|row| self.map[row * width + x..row * width + x_end].iter())
+            .copied()
+            .filter(|value| !value.is_nan())
+            .fold(None, |range, value| match range {
+                Some((min, max)) => Some((value.min(min), value.max(max))),
+                None => Some((value, value)),
+            })
     }
-}
 
-impl<'a> IntoIterator for &'a mut NoiseMap {
-    type Item = &'a mut f64;
+    /// Estimates the dominant spatial frequency of the map on each axis, in cycles per cell.
+    ///
+    /// The estimate is the position of the largest bin in the magnitude spectrum of the map's
+    /// 2D FFT, ignoring the DC bin. Bins above the Nyquist frequency are folded back, so each
+    /// component lies in `0.0..=0.5`. Returns `(0.0, 0.0)` for maps with no non-DC bins.
+    #[cfg(feature = "fft")]
+    pub fn dominant_frequency(&self) -> (f64, f64) {
+        use rustfft::{num_complex::Complex, FftPlanner};
 
-    type IntoIter = IterMut<'a, f64>;
+        let (width, height) = self.size;
 
-    fn into_iter(self) -> Self::IntoIter {
-        self.iter_mut()
+        if width * height <= 1 {
+            return (0.0, 0.0);
+        }
+
+        let mut spectrum: Vec<Complex<f64>> = self.map[..width * height]
+            .iter()
+            .map(|&value| Complex::new(value, 0.0))
+            .collect();
+
+        let mut planner = FftPlanner::new();
+        planner.plan_fft_forward(width).process(&mut spectrum);
+
+        let column_fft = planner.plan_fft_forward(height);
+        let mut column = vec![Complex::new(0.0, 0.0); height];
+        for x in 0..width {
+            for (y, value) in column.iter_mut().enumerate() {
+                *value = spectrum[x + y * width];
+            }
+            column_fft.process(&mut column);
+            for (y, value) in column.iter().enumerate() {
+                spectrum[x + y * width] = *value;
+            }
+        }
+
+        let mut peak = (0, 0);
+        let mut peak_magnitude = f64::NEG_INFINITY;
+        for (i, value) in spectrum.iter().enumerate().skip(1) {
+            let magnitude = value.norm_sqr();
+
+            if magnitude > peak_magnitude {
+                peak = (i % width, i / width);
+                peak_magnitude = magnitude;
+            }
+        }
+
+        let fold = |bin: usize, size: usize| bin.min(size - bin) as f64 / size as f64;
+
+        (fold(peak.0, width), fold(peak.1, height))
+    }
+
+    /// Walks from `start` in steps of `dir` and returns the first cell whose value is above
+    /// `level`, including `start` itself.
+    ///
+    /// Returns `None` if the walk leaves the map first, or if `start` is outside the map. A zero
+    /// `dir` only checks `start`.
+    pub fn first_above_along(
+        &self,
+        start: (usize, usize),
+        dir: (i32, i32),
+        level: f64,
+    ) -> Option<(usize, usize)> {
+        let (width, height) = self.size;
+        let (mut x, mut y) = (start.0 as i64, start.1 as i64);
+
+        loop {
+            if !(0..width as i64).contains(&x) || !(0..height as i64).contains(&y) {
+                return None;
+            }
+
+            let cell = (x as usize, y as usize);
+            if self[cell] > level {
+                return Some(cell);
+            }
+            if dir == (0, 0) {
+                return None;
+            }
+
+            x += i64::from(dir.0);
+            y += i64::from(dir.1);
+        }
+    }
+
+    fn find_extremum<F>(&self, is_better: F) -> Option<(usize, usize)>
+    where
+        F: Fn(f64, f64) -> bool,
+    {
+        let (width, height) = self.size;
+        let mut best: Option<((usize, usize), f64)> = None;
+
+        for y in 0..height {
+            for x in 0..width {
+                let value = self[(x, y)];
+
+                if value.is_nan() {
+                    continue;
+                }
+
+                match best {
+                    Some((_, best_value)) if !is_better(value, best_value) => {}
+                    _ => best = Some(((x, y), value)),
+                }
+            }
+        }
+
+        best.map(|(coordinates, _)| coordinates)
+    }
+
+    /// Labels the 4-connected regions of cells whose value is above `threshold`.
+    ///
+    /// Returns one label per cell in row-major order, together with the number of regions.
+    /// Cells at or below the threshold (and NaN cells) are labeled 0, and regions are numbered
+    /// from 1 in the row-major order of their first cell.
+    pub fn label_regions(&self, threshold: f64) -> (Vec<u32>, u32) {
+        self.label_regions_with(threshold, Connectivity::Four)
+    }
+
+    /// Counts the 4-connected islands of cells above `sea_level`.
+    pub fn count_islands(&self, sea_level: f64) -> usize {
+        self.count_islands_with(sea_level, Connectivity::Four)
+    }
+
+    /// Counts the islands of cells above `sea_level`, connected according to `connectivity`.
+    ///
+    /// With [`Connectivity::Eight`], cells touching only at a corner belong to the same island.
+    pub fn count_islands_with(&self, sea_level: f64, connectivity: Connectivity) -> usize {
+        self.label_regions_with(sea_level, connectivity).1 as usize
+    }
+
+    fn label_regions_with(&self, threshold: f64, connectivity: Connectivity) -> (Vec<u32>, u32) {
+        let (width, height) = self.size;
+        let mut labels = vec![0; width * height];
+        let mut count = 0;
+        let mut stack = Vec::new();
+        let is_above = |index: usize| self.map[index] > threshold;
+
+        for start in 0..width * height {
+            if labels[start] != 0 || !is_above(start) {
+                continue;
+            }
+
+            count += 1;
+            labels[start] = count;
+            stack.push(start);
+
+            while let Some(index) = stack.pop() {
+                let (x, y) = (index % width, index / width);
+                let (left, right) = (x > 0, x + 1 < width);
+                let (up, down) = (y > 0, y + 1 < height);
+                let diagonal = connectivity == Connectivity::Eight;
+                let neighbors = [
+                    left.then(|| index - 1),
+                    right.then(|| index + 1),
+                    up.then(|| index - width),
+                    down.then(|| index + width),
+                    (diagonal && left && up).then(|| index - width - 1),
+                    (diagonal && right && up).then(|| index - width + 1),
+                    (diagonal && left && down).then(|| index + width - 1),
+                    (diagonal && right && down).then(|| index + width + 1),
+                ];
+
+                for neighbor in neighbors.iter().flatten() {
+                    if labels[*neighbor] == 0 && is_above(*neighbor) {
+                        labels[*neighbor] = count;
+                        stack.push(*neighbor);
+                    }
+                }
+            }
+        }
+
+        (labels, count)
+    }
+
+    /// Returns a new map with the values histogram-equalized across `[-1, 1]`.
+    ///
+    /// The finite values are sorted into `bins` buckets, and each value is remapped via the
+    /// cumulative distribution of its bucket. NaN values are skipped and remain NaN.
+    pub fn equalize(&self, bins: usize) -> Self {
+        assert!(bins > 0);
+
+        let (width, height) = self.size;
+        let mut result = Self::new(width, height).set_border_value(self.border_value);
+
+        let (min, max) = self
+            .iter()
+            .take(width * height)
+            .filter(|value| !value.is_nan())
+            .fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), &value| {
+                (min.min(value), max.max(value))
+            });
+
+        let bin_of = |value: f64| {
+            if max > min {
+                (((value - min) / (max - min) * bins as f64) as usize).min(bins - 1)
+            } else {
+                0
+            }
+        };
+
+        let mut cdf = vec![0usize; bins];
+        for y in 0..height {
+            for x in 0..width {
+                let value = self[(x, y)];
+                if !value.is_nan() {
+                    cdf[bin_of(value)] += 1;
+                }
+            }
+        }
+        for i in 1..bins {
+            cdf[i] += cdf[i - 1];
+        }
+
+        let total = cdf[bins - 1];
+        let cdf_min = cdf.iter().copied().find(|&count| count > 0).unwrap_or(0);
+
+        for y in 0..height {
+            for x in 0..width {
+                let value = self[(x, y)];
+
+                result[(x, y)] = if value.is_nan() {
+                    value
+                } else if total > cdf_min {
+                    let cdf_value =
+                        (cdf[bin_of(value)] - cdf_min) as f64 / (total - cdf_min) as f64;
+                    cdf_value * 2.0 - 1.0
+                } else {
+                    0.0
+                };
+            }
+        }
+
+        result
+    }
+
+    /// Returns the next mip level of the map, half as wide and half as tall (but at least one
+    /// cell on each axis). Empty maps are returned unchanged.
+    ///
+    /// Each output cell is a Gaussian-weighted average of the 4x4 block of source cells around
+    /// its center, using the binomial weights `[1, 3, 3, 1] / 8` on each axis. This suppresses
+    /// the aliasing that plain 2x2 averaging introduces. Cells outside the map are clamped to the
+    /// nearest edge.
+    pub fn mipmap(&self) -> Self {
+        const WEIGHTS: [f64; 4] = [1.0 / 8.0, 3.0 / 8.0, 3.0 / 8.0, 1.0 / 8.0];
+
+        let (width, height) = self.size;
+
+        if width == 0 || height == 0 {
+            return self.clone();
+        }
+
+        let (mip_width, mip_height) = ((width / 2).max(1), (height / 2).max(1));
+
+        // Axes that are already a single cell wide are not halved, so their step is one cell.
+        let x_scale = width / mip_width;
+        let y_scale = height / mip_height;
+
+        Self::from_fn(mip_width, mip_height, |x, y| {
+            let mut total = 0.0;
+
+            for (j, y_weight) in WEIGHTS.iter().enumerate() {
+                let sample_y = (y * y_scale + j).saturating_sub(1).min(height - 1);
+
+                for (i, x_weight) in WEIGHTS.iter().enumerate() {
+                    let sample_x = (x * x_scale + i).saturating_sub(1).min(width - 1);
+
+                    total += x_weight * y_weight * self[(sample_x, sample_y)];
+                }
+            }
+
+            total
+        })
+        .set_border_value(self.border_value)
+    }
+
+    /// Returns the full mip chain of the map, starting with a copy of the map itself and
+    /// followed by successive [`mipmap`](Self::mipmap) levels down to 1x1. The chain of an
+    /// empty map holds only the map itself.
+    pub fn mip_chain(&self) -> Vec<NoiseMap> {
+        let mut chain = vec![self.clone()];
+
+        while let Some(level) = chain.last().filter(|level| {
+            let (width, height) = level.size();
+            width * height > 1
+        }) {
+            let next = level.mipmap();
+            chain.push(next);
+        }
+
+        chain
+    }
+
+    /// Returns a copy of the map downscaled with bilinear sampling so that its larger dimension
+    /// is `max_dim` cells (but at least one), preserving the aspect ratio. Maps that already fit
+    /// are returned unchanged.
+    pub fn thumbnail(&self, max_dim: usize) -> Self {
+        let (width, height) = self.size;
+        let largest = width.max(height);
+
+        if largest <= max_dim {
+            return self.clone();
+        }
+
+        let scale = max_dim as f64 / largest as f64;
+        let thumb_width = ((width as f64 * scale).round() as usize).max(1);
+        let thumb_height = ((height as f64 * scale).round() as usize).max(1);
+
+        // Cell centers of the thumbnail are mapped onto cell centers of the source.
+        let x_ratio = width as f64 / thumb_width as f64;
+        let y_ratio = height as f64 / thumb_height as f64;
+
+        Self::from_fn(thumb_width, thumb_height, |x, y| {
+            self.sample_bilinear(
+                (x as f64 + 0.5) * x_ratio - 0.5,
+                (y as f64 + 0.5) * y_ratio - 0.5,
+            )
+        })
+        .set_border_value(self.border_value)
+    }
+
+    /// Returns a copy of the map resized to `width` x `height` using nearest-neighbor sampling.
+    pub fn resize_nearest(&self, width: usize, height: usize) -> Self {
+        let (src_width, src_height) = self.size;
+
+        Self::from_fn(width, height, |x, y| {
+            self[(x * src_width / width, y * src_height / height)]
+        })
+        .set_border_value(self.border_value)
+    }
+
+    /// Replaces every NaN value with the average of its finite 8-neighbors.
+    ///
+    /// Passes are repeated until no NaN values remain or `width + height` passes have run, so
+    /// larger NaN regions are filled inwards from their edges. Any NaN values left after that,
+    /// such as in a map that is entirely NaN, are set to 0.0.
+    pub fn fill_nan(&mut self) {
+        let (width, height) = self.size;
+
+        for _ in 0..width + height {
+            let source = self.clone();
+            let mut remaining = false;
+
+            for y in 0..height {
+                for x in 0..width {
+                    if !source[(x, y)].is_nan() {
+                        continue;
+                    }
+
+                    let (mut sum, mut count) = (0.0, 0);
+                    for ny in y.saturating_sub(1)..(y + 2).min(height) {
+                        for nx in x.saturating_sub(1)..(x + 2).min(width) {
+                            let value = source[(nx, ny)];
+                            if value.is_finite() {
+                                sum += value;
+                                count += 1;
+                            }
+                        }
+                    }
+
+                    if count > 0 {
+                        self[(x, y)] = sum / count as f64;
+                    } else {
+                        remaining = true;
+                    }
+                }
+            }
+
+            if !remaining {
+                return;
+            }
+        }
+
+        for value in self.as_mut_slice() {
+            if value.is_nan() {
+                *value = 0.0;
+            }
+        }
+    }
+
+    /// Returns a new map with the `kernel_width` x `kernel_height` kernel applied to every cell.
+    ///
+    /// The kernel is given in row-major order and centered on each cell, and cells outside the
+    /// map are clamped to the nearest edge.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `kernel.len() != kernel_width * kernel_height`.
+    pub fn convolve(&self, kernel: &[f64], kernel_width: usize, kernel_height: usize) -> Self {
+        assert_eq!(
+            kernel.len(),
+            kernel_width * kernel_height,
+            "kernel length does not match its dimensions"
+        );
+
+        let (width, height) = self.size;
+
+        Self::from_fn(width, height, |x, y| {
+            let mut sum = 0.0;
+
+            for ky in 0..kernel_height {
+                let sy = (y + ky).saturating_sub(kernel_height / 2).min(height - 1);
+
+                for kx in 0..kernel_width {
+                    let sx = (x + kx).saturating_sub(kernel_width / 2).min(width - 1);
+
+                    sum += self[(sx, sy)] * kernel[kx + ky * kernel_width];
+                }
+            }
+
+            sum
+        })
+        .set_border_value(self.border_value)
+    }
+
+    /// Like [`convolve`](Self::convolve), but divides the kernel by its sum first. Kernels that
+    /// sum to zero are applied unchanged.
+    pub fn convolve_normalized(
+        &self,
+        kernel: &[f64],
+        kernel_width: usize,
+        kernel_height: usize,
+    ) -> Self {
+        let sum: f64 = kernel.iter().sum();
+
+        if sum == 0.0 {
+            self.convolve(kernel, kernel_width, kernel_height)
+        } else {
+            let kernel: Vec<f64> = kernel.iter().map(|weight| weight / sum).collect();
+
+            self.convolve(&kernel, kernel_width, kernel_height)
+        }
+    }
+
+    /// Returns a new map where each cell is the average of the `(2 * radius + 1)^2` square
+    /// around it, with cells outside the map clamped to the nearest edge.
+    pub fn box_blur(&self, radius: usize) -> Self {
+        let (width, height) = self.size;
+        let count = ((2 * radius + 1) * (2 * radius + 1)) as f64;
+
+        Self::from_fn(width, height, |x, y| {
+            let mut sum = 0.0;
+
+            for sy in y as isize - radius as isize..=(y + radius) as isize {
+                for sx in x as isize - radius as isize..=(x + radius) as isize {
+                    let sx = sx.clamp(0, width as isize - 1) as usize;
+                    let sy = sy.clamp(0, height as isize - 1) as usize;
+
+                    sum += self[(sx, sy)];
+                }
+            }
+
+            sum / count
+        })
+        .set_border_value(self.border_value)
+    }
+
+    /// Returns a new map smoothed by a bilateral filter, which blurs within regions of similar
+    /// value while preserving sharp transitions between them.
+    ///
+    /// Each cell becomes a weighted average of the cells within `ceil(3 * spatial_sigma)` of it,
+    /// where a neighbor's weight is the product of Gaussians of its distance, with standard
+    /// deviation `spatial_sigma`, and of its difference in value, with standard deviation
+    /// `range_sigma`. Cells outside the map are clamped to the nearest edge. If either standard
+    /// deviation is zero or negative, the filter has no effect and a copy of the map is returned.
+    pub fn bilateral_blur(&self, spatial_sigma: f64, range_sigma: f64) -> Self {
+        if spatial_sigma <= 0.0 || range_sigma <= 0.0 {
+            return self.clone();
+        }
+
+        let (width, height) = self.size;
+        let radius = (3.0 * spatial_sigma).ceil().max(0.0) as isize;
+        let spatial_scale = -0.5 / (spatial_sigma * spatial_sigma);
+        let range_scale = -0.5 / (range_sigma * range_sigma);
+
+        Self::from_fn(width, height, |x, y| {
+            let center = self[(x, y)];
+            let (mut sum, mut total_weight) = (0.0, 0.0);
+
+            for dy in -radius..=radius {
+                for dx in -radius..=radius {
+                    let sx = (x as isize + dx).clamp(0, width as isize - 1) as usize;
+                    let sy = (y as isize + dy).clamp(0, height as isize - 1) as usize;
+                    let value = self[(sx, sy)];
+
+                    let distance_squared = (dx * dx + dy * dy) as f64;
+                    let difference = value - center;
+                    let weight = (distance_squared * spatial_scale
+                        + difference * difference * range_scale)
+                        .exp();
+
+                    sum += weight * value;
+                    total_weight += weight;
+                }
+            }
+
+            sum / total_weight
+        })
+        .set_border_value(self.border_value)
+    }
+
+    /// Returns a new map that selects between `low` and `high` using this map as the control.
+    ///
+    /// Cells whose control value lies below `bounds.0` take their value from `low`, and cells
+    /// whose control value lies above `bounds.1` take theirs from `high`. Between the bounds the
+    /// two maps are blended, moving from `low` to `high` at a steady rate except within
+    /// `falloff` of either bound, where the rate eases in and out so the blend has no corners.
+    /// `falloff` is clamped to half the distance between the bounds; at that limit the whole
+    /// transition is an S-curve, and at zero it is a linear ramp. If the bounds coincide, the
+    /// selection switches from `low` to `high` at the bound.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the three maps differ in size.
+    pub fn select(
+        &self,
+        low: &NoiseMap,
+        high: &NoiseMap,
+        bounds: (f64, f64),
+        falloff: f64,
+    ) -> Self {
+        assert_eq!(self.size, low.size, "noise map sizes must match");
+        assert_eq!(self.size, high.size, "noise map sizes must match");
+
+        let (width, height) = self.size;
+        let (lower, upper) = bounds;
+        let range = upper - lower;
+        let falloff = falloff.clamp(0.0, (range / 2.0).max(0.0));
+
+        // The blend rate rises linearly over the first `falloff` of the range, stays at `rate`,
+        // and falls linearly over the last `falloff`, so the blend factor integrates to 1.0.
+        let rate = 1.0 / (range - falloff);
+        let blend_factor = |control: f64| {
+            let t = control - lower;
+
+            if t <= 0.0 {
+                0.0
+            } else if t >= range {
+                1.0
+            } else if t < falloff {
+                rate * t * t / (2.0 * falloff)
+            } else if t > range - falloff {
+                1.0 - rate * (range - t) * (range - t) / (2.0 * falloff)
+            } else {
+                rate * (t - falloff / 2.0)
+            }
+        };
+
+        Self::from_fn(width, height, |x, y| {
+            let control = self[(x, y)];
+            let (low_value, high_value) = (low[(x, y)], high[(x, y)]);
+
+            if range > 0.0 {
+                interpolate::linear(low_value, high_value, blend_factor(control))
+            } else if control < lower {
+                low_value
+            } else {
+                high_value
+            }
+        })
+        .set_border_value(self.border_value)
+    }
+
+    /// Returns this map multiplied by a feathered version of `mask`.
+    ///
+    /// Cells where `mask` is above 0.0 are inside the mask. Each inside cell is weighted by its
+    /// distance to the nearest outside cell divided by `feather_px`, capped at 1.0, so the mask's
+    /// edge fades in over `feather_px` cells regardless of the mask's own values. Outside cells
+    /// become 0.0. Distances use 8-connected chamfer steps of 1 and √2, and the edges of the map
+    /// are not treated as mask boundaries.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the two maps differ in size.
+    pub fn feathered_mask(&self, mask: &NoiseMap, feather_px: usize) -> Self {
+        assert_eq!(self.size, mask.size, "noise map sizes must match");
+
+        let (width, height) = self.size;
+        let mut distance: Vec<f64> = (0..width * height)
+            .map(|i| {
+                if mask.map[i] > 0.0 {
+                    f64::INFINITY
+                } else {
+                    0.0
+                }
+            })
+            .collect();
+
+        // Two chamfer passes, first from the top left and then from the bottom right.
+        let diagonal = core::f64::consts::SQRT_2;
+        let passes: [&[(isize, isize, f64)]; 2] = [
+            &[
+                (-1, 0, 1.0),
+                (-1, -1, diagonal),
+                (0, -1, 1.0),
+                (1, -1, diagonal),
+            ],
+            &[
+                (1, 0, 1.0),
+                (1, 1, diagonal),
+                (0, 1, 1.0),
+                (-1, 1, diagonal),
+            ],
+        ];
+
+        for (pass, neighbors) in passes.iter().enumerate() {
+            for step in 0..width * height {
+                let index = if pass == 0 {
+                    step
+                } else {
+                    width * height - 1 - step
+                };
+                let (x, y) = ((index % width) as isize, (index / width) as isize);
+
+                for &(dx, dy, cost) in neighbors.iter() {
+                    let (nx, ny) = (x + dx, y + dy);
+
+                    if (0..width as isize).contains(&nx) && (0..height as isize).contains(&ny) {
+                        let neighbor = distance[ny as usize * width + nx as usize];
+                        distance[index] = distance[index].min(neighbor + cost);
+                    }
+                }
+            }
+        }
+
+        Self::from_fn(width, height, |x, y| {
+            let distance = distance[y * width + x];
+            let weight = if distance == 0.0 {
+                0.0
+            } else if feather_px == 0 {
+                1.0
+            } else {
+                (distance / feather_px as f64).min(1.0)
+            };
+
+            self[(x, y)] * weight
+        })
+        .set_border_value(self.border_value)
+    }
+
+    /// Returns the mean structural similarity index (SSIM) between this map and `other`, over
+    /// every `window` by `window` square of cells.
+    ///
+    /// The index is 1.0 for identical maps and decreases as their local means, contrasts and
+    /// correlations diverge, which makes it a tolerant way of checking that two maps look the
+    /// same. The stabilizing constants assume values span `[-1, 1]`. Windows larger than the
+    /// map are shrunk to fit it, and empty maps return 1.0.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the two maps differ in size or `window` is 0.
+    pub fn ssim(&self, other: &NoiseMap, window: usize) -> f64 {
+        assert_eq!(self.size, other.size, "noise map sizes must match");
+        assert!(window > 0, "window must be positive");
+
+        const DYNAMIC_RANGE: f64 = 2.0;
+        let c1 = (0.01 * DYNAMIC_RANGE) * (0.01 * DYNAMIC_RANGE);
+        let c2 = (0.03 * DYNAMIC_RANGE) * (0.03 * DYNAMIC_RANGE);
+
+        let (width, height) = self.size;
+        let (window_width, window_height) = (window.min(width), window.min(height));
+        if window_width == 0 || window_height == 0 {
+            return 1.0;
+        }
+
+        let count = (window_width * window_height) as f64;
+        let mut total = 0.0;
+        let mut windows = 0;
+
+        for top in 0..=height - window_height {
+            for left in 0..=width - window_width {
+                let cells = (top..top + window_height)
+                    .flat_map(|y| (left..left + window_width).map(move |x| (x, y)));
+
+                let (mut sum_a, mut sum_b) = (0.0, 0.0);
+                for cell in cells.clone() {
+                    sum_a += self[cell];
+                    sum_b += other[cell];
+                }
+                let (mean_a, mean_b) = (sum_a / count, sum_b / count);
+
+                let (mut variance_a, mut variance_b, mut covariance) = (0.0, 0.0, 0.0);
+                for cell in cells {
+                    let (da, db) = (self[cell] - mean_a, other[cell] - mean_b);
+
+                    variance_a += da * da;
+                    variance_b += db * db;
+                    covariance += da * db;
+                }
+                let (variance_a, variance_b, covariance) =
+                    (variance_a / count, variance_b / count, covariance / count);
+
+                total += ((2.0 * mean_a * mean_b + c1) * (2.0 * covariance + c2))
+                    / ((mean_a * mean_a + mean_b * mean_b + c1) * (variance_a + variance_b + c2));
+                windows += 1;
+            }
+        }
+
+        total / windows as f64
+    }
+
+    /// Returns a new map that combines this map (the base) with `other` using `mode`.
+    ///
+    /// Values are mapped from `[-1, 1]` to `[0, 1]` and clamped before blending, and the result
+    /// is mapped back to `[-1, 1]`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the two maps differ in size.
+    pub fn blend_mode(&self, other: &NoiseMap, mode: BlendMode) -> Self {
+        assert_eq!(self.size, other.size, "noise map sizes must match");
+
+        let (width, height) = self.size;
+        let normalize = |value: f64| (value * 0.5 + 0.5).clamp(0.0, 1.0);
+
+        Self::from_fn(width, height, |x, y| {
+            let blended = mode.apply(normalize(self[(x, y)]), normalize(other[(x, y)]));
+
+            blended * 2.0 - 1.0
+        })
+        .set_border_value(self.border_value)
+    }
+
+    /// Returns a new map with `other` placed to the right of this map.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the heights of the two maps differ.
+    pub fn concat_h(&self, other: &NoiseMap) -> Self {
+        let (width, height) = self.size;
+        let (other_width, other_height) = other.size;
+
+        assert_eq!(height, other_height, "noise map heights must match");
+
+        Self::from_fn(width + other_width, height, |x, y| {
+            if x < width {
+                self[(x, y)]
+            } else {
+                other[(x - width, y)]
+            }
+        })
+        .set_border_value(self.border_value)
+    }
+
+    /// Returns a new map with `other` placed below this map.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the widths of the two maps differ.
+    pub fn concat_v(&self, other: &NoiseMap) -> Self {
+        let (width, height) = self.size;
+        let (other_width, other_height) = other.size;
+
+        assert_eq!(width, other_width, "noise map widths must match");
+
+        Self::from_fn(width, height + other_height, |x, y| {
+            if y < height {
+                self[(x, y)]
+            } else {
+                other[(x, y - height)]
+            }
+        })
+        .set_border_value(self.border_value)
+    }
+
+    /// Returns a copy of the map folded into `segments` mirrored wedges around its center, for
+    /// kaleidoscope-like radial symmetry.
+    ///
+    /// Angles are measured around the center from the `+y` direction. Every output cell is mapped
+    /// back into the first wedge, mirroring every other wedge so neighboring wedges meet
+    /// seamlessly, and the map is sampled there with
+    /// [`sample_bilinear`](Self::sample_bilinear). Two segments mirror the map's right half onto
+    /// its left half. Zero or one segment returns an unchanged copy.
+    pub fn kaleidoscope(&self, segments: usize) -> Self {
+        if segments <= 1 {
+            return self.clone();
+        }
+
+        let (width, height) = self.size;
+        let (center_x, center_y) = (
+            width.saturating_sub(1) as f64 / 2.0,
+            height.saturating_sub(1) as f64 / 2.0,
+        );
+        let wedge = core::f64::consts::TAU / segments as f64;
+
+        Self::from_fn(width, height, |x, y| {
+            let (dx, dy) = (x as f64 - center_x, y as f64 - center_y);
+            let radius = (dx * dx + dy * dy).sqrt();
+            let angle = dx.atan2(dy).rem_euclid(core::f64::consts::TAU);
+
+            let index = (angle / wedge).floor();
+            let local = angle - index * wedge;
+            let local = if index as usize % 2 == 1 {
+                wedge - local
+            } else {
+                local
+            };
+
+            self.sample_bilinear(
+                center_x + radius * local.sin(),
+                center_y + radius * local.cos(),
+            )
+        })
+        .set_border_value(self.border_value)
+    }
+
+    /// Returns a copy of the map swirled about its center.
+    ///
+    /// Each cell samples the map, with [`sample_bilinear`](Self::sample_bilinear), at its own
+    /// position rotated about the center by `strength * distance` radians, where `distance` is
+    /// its distance from the center in cells. Positive strengths rotate the sampled position
+    /// from `+x` towards `+y`, so the contents appear to turn the other way.
+    pub fn swirl(&self, strength: f64) -> Self {
+        let (width, height) = self.size;
+        let (center_x, center_y) = (
+            width.saturating_sub(1) as f64 / 2.0,
+            height.saturating_sub(1) as f64 / 2.0,
+        );
+
+        Self::from_fn(width, height, |x, y| {
+            let (dx, dy) = (x as f64 - center_x, y as f64 - center_y);
+            let angle = strength * (dx * dx + dy * dy).sqrt();
+            let (sin, cos) = angle.sin_cos();
+
+            self.sample_bilinear(
+                center_x + dx * cos - dy * sin,
+                center_y + dx * sin + dy * cos,
+            )
+        })
+        .set_border_value(self.border_value)
+    }
+
+    /// Returns a copy of the map with its contents rolled by `(dx, dy)` cells, wrapping around
+    /// the edges. A value at `(x, y)` moves to `(x + dx, y + dy)`.
+    pub fn shifted(&self, dx: i64, dy: i64) -> Self {
+        let (width, height) = self.size;
+
+        Self::from_fn(width, height, |x, y| {
+            let source_x = (x as i64 - dx).rem_euclid(width as i64) as usize;
+            let source_y = (y as i64 - dy).rem_euclid(height as i64) as usize;
+
+            self[(source_x, source_y)]
+        })
+        .set_border_value(self.border_value)
+    }
+
+    #[cfg(feature = "images")]
+    pub fn write_to_file(&self, filename: &Path) {
+        // collect the values from f64 into u8 in a separate vec
+        let (width, height) = self.size;
+        let mut pixels: Vec<u8> = Vec::with_capacity(width * height);
+
+        for i in &self.map {
+            pixels.push(value_to_u8(*i));
+        }
+
+        let _ = image::save_buffer(
+            filename,
+            &pixels,
+            self.size.0 as u32,
+            self.size.1 as u32,
+            image::ColorType::L8,
+        );
+
+        println!("\nFinished generating {}", filename.to_string_lossy());
+    }
+
+    /// Writes the map as an RGB PNG using a diverging colormap centered at zero.
+    ///
+    /// Values from -1.0 to 0.0 blend from `neg` to `mid`, and values from 0.0 to 1.0 blend from
+    /// `mid` to `pos`. Values outside `[-1, 1]` are clamped.
+    #[cfg(feature = "images")]
+    pub fn write_to_png_diverging(
+        &self,
+        filename: &Path,
+        neg: [u8; 3],
+        mid: [u8; 3],
+        pos: [u8; 3],
+    ) -> io::Result<()> {
+        let (width, height) = self.size;
+        let mut pixels: Vec<u8> = Vec::with_capacity(width * height * 3);
+
+        for y in 0..height {
+            for x in 0..width {
+                pixels.extend_from_slice(&diverging_color(self[(x, y)], neg, mid, pos));
+            }
+        }
+
+        image::save_buffer(
+            filename,
+            &pixels,
+            width as u32,
+            height as u32,
+            image::ColorType::Rgb8,
+        )
+        .map_err(io::Error::other)
+    }
+
+    /// Writes the map as a 16-bit grayscale PNG heightmap, mapping `[-1, 1]` to `[0, 65535]`.
+    /// Values outside `[-1, 1]` are clamped.
+    #[cfg(feature = "images")]
+    pub fn write_to_heightmap_png(&self, filename: &Path) -> io::Result<()> {
+        let (width, height) = self.size;
+        let mut pixels: Vec<u16> = Vec::with_capacity(width * height);
+
+        for y in 0..height {
+            for x in 0..width {
+                let value = (self[(x, y)] * 0.5 + 0.5).clamp(0.0, 1.0);
+                pixels.push((value * f64::from(u16::MAX)).round() as u16);
+            }
+        }
+
+        image::ImageBuffer::<image::Luma<u16>, _>::from_raw(width as u32, height as u32, pixels)
+            .expect("pixel buffer matches the map size")
+            .save_with_format(filename, image::ImageFormat::Png)
+            .map_err(io::Error::other)
+    }
+
+    /// Writes the map as a single-band, 32-bit floating point TIFF.
+    ///
+    /// Values are written as-is, without any georeferencing tags.
+    #[cfg(feature = "tiff")]
+    pub fn write_to_tiff(&self, filename: &Path) -> io::Result<()> {
+        let (width, height) = self.size;
+        let mut pixels: Vec<f32> = Vec::with_capacity(width * height);
+
+        for y in 0..height {
+            for x in 0..width {
+                pixels.push(self[(x, y)] as f32);
+            }
+        }
+
+        let file = io::BufWriter::new(File::create(filename)?);
+
+        tiff::encoder::TiffEncoder::new(file)
+            .and_then(|mut encoder| {
+                encoder.write_image::<tiff::encoder::colortype::Gray32Float>(
+                    width as u32,
+                    height as u32,
+                    &pixels,
+                )
+            })
+            .map_err(io::Error::other)
+    }
+
+    /// Writes the map as a binary (P6) PPM image, mapping `[-1, 1]` to `[0, 255]` grayscale.
+    #[cfg(feature = "std")]
+    pub fn write_to_ppm(&self, filename: &Path) -> io::Result<()> {
+        let (width, height) = self.size;
+        let mut pixels: Vec<u8> = Vec::with_capacity(width * height * 3);
+
+        for y in 0..height {
+            for x in 0..width {
+                let value = value_to_u8(self[(x, y)]);
+                pixels.extend_from_slice(&[value, value, value]);
+            }
+        }
+
+        let mut file = io::BufWriter::new(File::create(filename)?);
+        write!(file, "P6\n{} {}\n255\n", width, height)?;
+        file.write_all(&pixels)?;
+        file.flush()
+    }
+
+    /// Writes the map to a run-length encoded file.
+    ///
+    /// Runs of bit-identical values are stored once with their length, so maps with large flat
+    /// regions (masks, terraces) are much smaller than their raw size. Read the file back with
+    /// [`read_from_rle`](Self::read_from_rle).
+    #[cfg(feature = "std")]
+    pub fn write_to_rle(&self, filename: &Path) -> io::Result<()> {
+        let (width, height) = self.size;
+        let mut file = io::BufWriter::new(File::create(filename)?);
+
+        file.write_all(RLE_MAGIC)?;
+        file.write_all(&(width as u32).to_le_bytes())?;
+        file.write_all(&(height as u32).to_le_bytes())?;
+
+        let values = &self.map[..width * height];
+        let mut start = 0;
+        while start < values.len() {
+            let bits = values[start].to_bits();
+            let run = values[start..]
+                .iter()
+                .take(u32::MAX as usize)
+                .take_while(|value| value.to_bits() == bits)
+                .count();
+
+            file.write_all(&(run as u32).to_le_bytes())?;
+            file.write_all(&bits.to_le_bytes())?;
+
+            start += run;
+        }
+
+        file.flush()
+    }
+
+    /// Reads a map written by [`write_to_rle`](Self::write_to_rle).
+    #[cfg(feature = "std")]
+    pub fn read_from_rle(filename: &Path) -> io::Result<Self> {
+        use core::convert::TryInto;
+
+        let bytes = std::fs::read(filename)?;
+        let invalid = |message| io::Error::new(io::ErrorKind::InvalidData, message);
+
+        let header_len = RLE_MAGIC.len() + 8;
+        if bytes.len() < header_len || &bytes[..RLE_MAGIC.len()] != RLE_MAGIC {
+            return Err(invalid("missing RLE noise map header"));
+        }
+
+        let read_u32 = |offset: usize| {
+            u32::from_le_bytes(bytes[offset..offset + 4].try_into().unwrap()) as usize
+        };
+        let width = read_u32(RLE_MAGIC.len());
+        let height = read_u32(RLE_MAGIC.len() + 4);
+        if width >= RASTER_MAX_WIDTH as usize || height >= RASTER_MAX_HEIGHT as usize {
+            return Err(invalid("RLE noise map is too large"));
+        }
+
+        // The header is untrusted, so the values only grow as runs are decoded.
+        let mut values = Vec::new();
+        for run in bytes[header_len..].chunks(12) {
+            if run.len() != 12 {
+                return Err(invalid("truncated RLE run"));
+            }
+
+            let count = u32::from_le_bytes(run[..4].try_into().unwrap()) as usize;
+            let value = f64::from_bits(u64::from_le_bytes(run[4..].try_into().unwrap()));
+
+            if values.len() + count > width * height {
+                return Err(invalid("RLE runs exceed the map size"));
+            }
+            values.resize(values.len() + count, value);
+        }
+
+        if values.len() != width * height {
+            return Err(invalid("RLE runs do not fill the map"));
+        }
+
+        let mut map = Self::new(width, height);
+        map.as_mut_slice().copy_from_slice(&values);
+
+        Ok(map)
+    }
+
+    /// Writes the map as comma-separated values, one line per row.
+    ///
+    /// Values use Rust's shortest round-trip formatting, so
+    /// [`read_from_csv`](Self::read_from_csv) restores them exactly.
+    #[cfg(feature = "std")]
+    pub fn write_to_csv(&self, filename: &Path) -> io::Result<()> {
+        let (width, height) = self.size;
+        let mut file = io::BufWriter::new(File::create(filename)?);
+
+        for y in 0..height {
+            for x in 0..width {
+                if x > 0 {
+                    file.write_all(b",")?;
+                }
+                write!(file, "{}", self[(x, y)])?;
+            }
+            file.write_all(b"\n")?;
+        }
+
+        file.flush()
+    }
+
+    /// Reads a map from comma-separated values, with one line per row. The dimensions are taken
+    /// from the shape of the grid, and rows of differing lengths are rejected.
+    #[cfg(feature = "std")]
+    pub fn read_from_csv(filename: &Path) -> io::Result<Self> {
+        let text = std::fs::read_to_string(filename)?;
+        let invalid = |message: String| io::Error::new(io::ErrorKind::InvalidData, message);
+
+        let mut values = Vec::new();
+        let mut width = None;
+        let mut height = 0;
+
+        for line in text.lines().filter(|line| !line.trim().is_empty()) {
+            let row = line
+                .split(',')
+                .map(|field| field.trim().parse::<f64>())
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|error| invalid(format!("invalid value on row {}: {}", height, error)))?;
+
+            match width {
+                Some(width) if width != row.len() => {
+                    return Err(invalid(format!(
+                        "row {} has {} values, expected {}",
+                        height,
+                        row.len(),
+                        width
+                    )));
+                }
+                _ => width = Some(row.len()),
+            }
+
+            values.extend(row);
+            height += 1;
+        }
+
+        let width = width.unwrap_or(0);
+        if width >= RASTER_MAX_WIDTH as usize || height >= RASTER_MAX_HEIGHT as usize {
+            return Err(invalid(String::from("CSV noise map is too large")));
+        }
+
+        let mut map = Self::new(width, height);
+        map.as_mut_slice().copy_from_slice(&values);
+
+        Ok(map)
+    }
+
+    fn initialize() -> Self {
+        Self {
+            size: (0, 0),
+            border_value: 0.0,
+            map: Vec::new(),
+        }
+    }
+}
+
+#[inline]
+fn value_to_u8(value: f64) -> u8 {
+    ((value * 0.5 + 0.5).clamp(0.0, 1.0) * 255.0) as u8
+}
+
+#[cfg(feature = "images")]
+fn diverging_color(value: f64, neg: [u8; 3], mid: [u8; 3], pos: [u8; 3]) -> [u8; 3] {
+    let value = value.clamp(-1.0, 1.0);
+    let (end, alpha) = if value < 0.0 {
+        (neg, -value)
+    } else {
+        (pos, value)
+    };
+
+    let mut color = [0; 3];
+    for i in 0..3 {
+        let channel = interpolate::linear(f64::from(mid[i]), f64::from(end[i]), alpha);
+        color[i] = channel.round() as u8;
+    }
+
+    color
+}
+
+/// Extracts the even-numbered bits of `code` into a contiguous integer, undoing the bit
+/// interleaving of a Morton code.
+fn compact_bits(code: usize) -> usize {
+    (0..usize::BITS / 2).fold(0, |result, bit| result | ((code >> (2 * bit)) & 1) << bit)
+}
+
+/// Computes the weighted sum of equally-sized noise maps.
+pub fn accumulate(maps: &[(&NoiseMap, f64)]) -> Result<NoiseMap, NoiseMapError> {
+    let (first, _) = maps.first().ok_or(NoiseMapError::EmptyInput)?;
+    let (width, height) = first.size();
+
+    if let Some((map, _)) = maps.iter().find(|(map, _)| map.size() != first.size()) {
+        return Err(NoiseMapError::SizeMismatch {
+            expected: first.size(),
+            found: map.size(),
+        });
+    }
+
+    let mut result = NoiseMap::new(width, height).set_border_value(first.border_value());
+
+    for y in 0..height {
+        for x in 0..width {
+            result[(x, y)] = maps.iter().map(|(map, weight)| map[(x, y)] * weight).sum();
+        }
+    }
+
+    Ok(result)
+}
+
+/// Combines three equally-sized maps into interleaved RGB bytes, one map per channel.
+///
+/// Each channel maps `[-1, 1]` to `[0, 255]`, clamping values outside that range. Pixels are in
+/// row-major order.
+///
+/// # Panics
+///
+/// Panics if the three maps differ in size.
+pub fn compose_rgb(r: &NoiseMap, g: &NoiseMap, b: &NoiseMap) -> Vec<u8> {
+    assert_eq!(r.size(), g.size(), "noise map sizes must match");
+    assert_eq!(r.size(), b.size(), "noise map sizes must match");
+
+    let (width, height) = r.size();
+    let mut pixels = Vec::with_capacity(width * height * 3);
+
+    for y in 0..height {
+        for x in 0..width {
+            pixels.extend_from_slice(&[
+                value_to_u8(r[(x, y)]),
+                value_to_u8(g[(x, y)]),
+                value_to_u8(b[(x, y)]),
+            ]);
+        }
+    }
+
+    pixels
+}
+
+/// Computes the per-cell weighted average of several layers, each given as a `(values,
+/// weights)` pair of maps.
+///
+/// Each output cell is `sum(value * weight) / sum(weight)` over all layers. Cells whose weights
+/// sum to zero are set to 0.0. All maps, values and weights alike, must share the size of the
+/// first value map.
+///
+/// # Panics
+///
+/// Panics if `layers` is empty or if any of its maps differ in size.
+pub fn weighted_blend(layers: &[(&NoiseMap, &NoiseMap)]) -> NoiseMap {
+    let (first, _) = layers.first().expect("at least one layer is required");
+    let (width, height) = first.size();
+
+    for (values, weights) in layers {
+        assert_eq!(first.size(), values.size(), "noise map sizes must match");
+        assert_eq!(first.size(), weights.size(), "noise map sizes must match");
+    }
+
+    let mut result = NoiseMap::new(width, height).set_border_value(first.border_value());
+
+    for y in 0..height {
+        for x in 0..width {
+            let (total, total_weight) =
+                layers
+                    .iter()
+                    .fold((0.0, 0.0), |(total, total_weight), (values, weights)| {
+                        let weight = weights[(x, y)];
+                        (total + values[(x, y)] * weight, total_weight + weight)
+                    });
+
+            result[(x, y)] = if total_weight != 0.0 {
+                total / total_weight
+            } else {
+                0.0
+            };
+        }
+    }
+
+    result
+}
+
+impl Default for NoiseMap {
+    fn default() -> Self {
+        Self::initialize()
+    }
+}
+
+impl Index<(usize, usize)> for NoiseMap {
+    type Output = f64;
+
+    fn index(&self, (x, y): (usize, usize)) -> &Self::Output {
+        let (width, height) = self.size;
+        if x < width && y < height {
+            &self.map[x + y * width]
+        } else {
+            &self.border_value
+        }
+    }
+}
+
+impl IndexMut<(usize, usize)> for NoiseMap {
+    fn index_mut(&mut self, (x, y): (usize, usize)) -> &mut Self::Output {
+        let (width, height) = self.size;
+        if x < width && y < height {
+            &mut self.map[x + y * width]
+        } else {
+            panic!(
+                "index ({}, {}) out of bounds for NoiseMap of size ({}, {})",
+                x, y, width, height
+            )
+        }
+    }
+}
+
+impl IntoIterator for NoiseMap {
+    type Item = f64;
+
+    type IntoIter = IntoIter<f64>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.map.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a NoiseMap {
+    type Item = &'a f64;
+
+    type IntoIter = Iter<'a, f64>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a mut NoiseMap {
+    type Item = &'a mut f64;
+
+    type IntoIter = IterMut<'a, f64>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ramp(width: usize, height: usize) -> NoiseMap {
+        let mut map = NoiseMap::new(width, height);
+
+        for y in 0..height {
+            for x in 0..width {
+                map[(x, y)] = (x + y * width) as f64 / (width * height - 1) as f64 * 2.0 - 1.0;
+            }
+        }
+
+        map
+    }
+
+    #[test]
+    fn smooth_threshold_endpoints_and_midpoint() {
+        let mut map = NoiseMap::new(3, 1);
+        map[(0, 0)] = -0.5;
+        map[(1, 0)] = 0.25;
+        map[(2, 0)] = 1.0;
+
+        let result = map.smooth_threshold(0.0, 0.5);
+
+        assert_eq!(0.0, result[(0, 0)]);
+        assert_eq!(0.5, result[(1, 0)]);
+        assert_eq!(1.0, result[(2, 0)]);
+
+        let result = ramp(4, 4).smooth_threshold(-1.0, 1.0);
+        assert_eq!(0.0, result[(0, 0)]);
+        assert_eq!(1.0, result[(3, 3)]);
+    }
+
+    #[test]
+    fn tiled_wraps_source() {
+        let map = ramp(4, 4);
+        let tiled = map.tiled(8, 8);
+
+        assert_eq!((8, 8), tiled.size());
+        assert_eq!(map[(1, 1)], tiled[(5, 5)]);
+        assert_eq!(map[(3, 0)], tiled[(7, 4)]);
+    }
+
+    #[test]
+    fn slope_of_flat_and_ramp() {
+        let mut flat = NoiseMap::new(5, 5);
+        flat.iter_mut().for_each(|value| *value = 0.3);
+        assert!(flat.slope().iter().all(|&value| value == 0.0));
+
+        let mut ramp = NoiseMap::new(5, 5);
+        for y in 0..5 {
+            for x in 0..5 {
+                ramp[(x, y)] = x as f64 * 0.25;
+            }
+        }
+        let slope = ramp.slope();
+        assert!(slope.iter().all(|&value| (value - 0.25).abs() < 1e-12));
+    }
+
+    #[test]
+    fn equalize_flattens_histogram() {
+        fn bin_count_variance(map: &NoiseMap, bins: usize) -> f64 {
+            let mut counts = vec![0.0; bins];
+            for &value in map.iter().filter(|value| !value.is_nan()) {
+                let bin = ((value * 0.5 + 0.5) * bins as f64) as usize;
+                counts[bin.min(bins - 1)] += 1.0;
+            }
+            let mean = counts.iter().sum::<f64>() / bins as f64;
+            counts
+                .iter()
+                .map(|count| (count - mean).powi(2))
+                .sum::<f64>()
+                / bins as f64
+        }
+
+        let mut map = ramp(16, 16);
+        map.iter_mut().for_each(|value| *value = value.powi(5));
+        map[(3, 3)] = f64::NAN;
+
+        let equalized = map.equalize(64);
+
+        assert!(equalized[(3, 3)].is_nan());
+        assert!(equalized
+            .iter()
+            .filter(|value| !value.is_nan())
+            .all(|value| (-1.0..=1.0).contains(value)));
+        assert!(bin_count_variance(&equalized, 8) < bin_count_variance(&map, 8));
+    }
+
+    #[test]
+    fn accumulate_weighted_average() {
+        let a = ramp(4, 4);
+        let b = ramp(4, 4).smooth_threshold(-1.0, 1.0);
+
+        let sum = accumulate(&[(&a, 0.5), (&b, 0.5)]).unwrap();
+
+        for y in 0..4 {
+            for x in 0..4 {
+                assert!((sum[(x, y)] - (a[(x, y)] + b[(x, y)]) / 2.0).abs() < 1e-12);
+            }
+        }
+
+        assert_eq!(Err(NoiseMapError::EmptyInput), accumulate(&[]).map(|_| ()));
+        assert_eq!(
+            Err(NoiseMapError::SizeMismatch {
+                expected: (4, 4),
+                found: (2, 2),
+            }),
+            accumulate(&[(&a, 1.0), (&NoiseMap::new(2, 2), 1.0)]).map(|_| ())
+        );
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn write_to_ppm_header_and_size() {
+        let map = ramp(5, 3);
+        let path = std::env::temp_dir().join("noise_map_write_to_ppm.ppm");
+
+        map.write_to_ppm(&path).unwrap();
+        let bytes = std::fs::read(&path).unwrap();
+        let _ = std::fs::remove_file(&path);
+
+        let header = b"P6\n5 3\n255\n";
+        assert_eq!(&header[..], &bytes[..header.len()]);
+        assert_eq!(header.len() + 5 * 3 * 3, bytes.len());
+        assert_eq!([0, 0, 0], bytes[header.len()..header.len() + 3]);
+        assert_eq!([255, 255, 255], bytes[bytes.len() - 3..]);
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn from_fn_par_matches_from_fn() {
+        let f = |x: usize, y: usize| ((x * 7 + y * 13) % 11) as f64 / 11.0 - (y as f64).sin();
+
+        let serial = NoiseMap::from_fn(37, 23, f);
+        let parallel = NoiseMap::from_fn_par(37, 23, f);
+
+        assert_eq!(serial.size(), parallel.size());
+        assert!(serial.iter().eq(parallel.iter()));
+        assert_eq!(f(5, 9), parallel[(5, 9)]);
+    }
+
+    #[test]
+    fn resize_nearest_produces_blocks() {
+        let map = ramp(2, 2);
+        let resized = map.resize_nearest(4, 4);
+
+        assert_eq!((4, 4), resized.size());
+        for y in 0..4 {
+            for x in 0..4 {
+                assert_eq!(map[(x / 2, y / 2)], resized[(x, y)]);
+            }
+        }
+    }
+
+    #[cfg(feature = "images")]
+    #[test]
+    fn diverging_colormap_endpoints() {
+        let (neg, mid, pos) = ([0, 0, 255], [255, 255, 255], [255, 0, 0]);
+
+        assert_eq!(mid, diverging_color(0.0, neg, mid, pos));
+        assert_eq!(neg, diverging_color(-1.0, neg, mid, pos));
+        assert_eq!(pos, diverging_color(1.0, neg, mid, pos));
+        assert_eq!(pos, diverging_color(3.0, neg, mid, pos));
+        assert_eq!([128, 128, 255], diverging_color(-0.5, neg, mid, pos));
+
+        let mut map = NoiseMap::new(3, 1);
+        map[(0, 0)] = -1.0;
+        map[(2, 0)] = 1.0;
+        let path = std::env::temp_dir().join("noise_map_write_to_png_diverging.png");
+        map.write_to_png_diverging(&path, neg, mid, pos).unwrap();
+        let image = image::open(&path).unwrap().into_rgb8();
+        let _ = std::fs::remove_file(&path);
+
+        assert_eq!(neg, image.get_pixel(0, 0).0);
+        assert_eq!(mid, image.get_pixel(1, 0).0);
+        assert_eq!(pos, image.get_pixel(2, 0).0);
+    }
+
+    #[test]
+    fn fill_nan_uses_neighbor_average() {
+        let mut map = NoiseMap::from_fn(3, 3, |_, _| 1.0);
+        map[(1, 1)] = f64::NAN;
+        map.fill_nan();
+        assert_eq!(1.0, map[(1, 1)]);
+
+        let mut map = NoiseMap::from_fn(5, 1, |x, _| x as f64);
+        map[(1, 0)] = f64::NAN;
+        map[(2, 0)] = f64::NAN;
+        map.fill_nan();
+        assert!(map.iter().all(|value| value.is_finite()));
+        assert_eq!(0.0, map[(1, 0)]);
+
+        let mut map = NoiseMap::from_fn(4, 4, |_, _| f64::NAN);
+        map.fill_nan();
+        assert!(map.iter().all(|&value| value == 0.0));
+    }
+
+    #[test]
+    fn iter_mut_matches_map() {
+        let mut map = ramp(6, 5);
+        let halved = map.map(|value| value * 0.5);
+
+        for value in map.iter_mut() {
+            *value *= 0.5;
+        }
+
+        assert!(map.iter().eq(halved.iter()));
+        assert_eq!(-0.5, map[(0, 0)]);
+    }
+
+    #[test]
+    fn convolve_kernels() {
+        let map = NoiseMap::from_fn(7, 5, |x, y| ((x * 3 + y * 5) % 7) as f64 / 7.0);
+
+        let doubled = map.convolve(&[2.0], 1, 1);
+        assert!(doubled.iter().zip(map.iter()).all(|(d, v)| *d == v * 2.0));
+
+        let blurred = map.convolve_normalized(&[1.0; 9], 3, 3);
+        let box_blurred = map.box_blur(1);
+        assert!(blurred
+            .iter()
+            .zip(box_blurred.iter())
+            .all(|(a, b)| (a - b).abs() < 1e-12));
+    }
+
+    #[test]
+    #[should_panic]
+    fn convolve_rejects_bad_kernel() {
+        let _ = ramp(3, 3).convolve(&[1.0; 4], 3, 3);
+    }
+
+    #[test]
+    fn concat_h_and_v() {
+        let left = NoiseMap::from_fn(2, 2, |x, y| (x + y * 2) as f64);
+        let right = left.map(|value| value + 10.0);
+
+        let wide = left.concat_h(&right);
+        assert_eq!((4, 2), wide.size());
+        for y in 0..2 {
+            assert_eq!(left[(1, y)], wide[(1, y)]);
+            assert_eq!(right[(0, y)], wide[(2, y)]);
+        }
+
+        let tall = left.concat_v(&right);
+        assert_eq!((2, 4), tall.size());
+        assert_eq!(left[(1, 1)], tall[(1, 1)]);
+        assert_eq!(right[(1, 0)], tall[(1, 2)]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn concat_h_rejects_mismatched_heights() {
+        let _ = NoiseMap::new(2, 2).concat_h(&NoiseMap::new(2, 3));
+    }
+
+    #[test]
+    fn shifted_round_trip() {
+        let map = ramp(5, 4);
+
+        let shifted = map.shifted(1, 0);
+        assert_eq!(map[(0, 2)], shifted[(1, 2)]);
+        assert_eq!(map[(4, 2)], shifted[(0, 2)]);
+        assert!(shifted.shifted(-1, 0).iter().eq(map.iter()));
+        assert!(map.shifted(7, -9).shifted(-7, 9).iter().eq(map.iter()));
+    }
+
+    #[test]
+    fn sample_bilinear_interpolates_cells() {
+        let map = NoiseMap::from_fn(3, 2, |x, y| (x + 3 * y) as f64);
+
+        assert_eq!(map[(2, 1)], map.sample_bilinear(2.0, 1.0));
+        assert_eq!(0.5, map.sample_bilinear(0.5, 0.0));
+        assert_eq!(2.0, map.sample_bilinear(0.5, 0.5));
+        assert_eq!(map[(2, 0)], map.sample_bilinear(9.0, -3.0));
+    }
+
+    #[cfg(feature = "images")]
+    #[test]
+    fn write_to_heightmap_png_is_16_bit() {
+        let mut map = ramp(6, 4);
+        map[(1, 0)] = 5.0;
+        let path = std::env::temp_dir().join("noise_map_write_to_heightmap_png.png");
+
+        map.write_to_heightmap_png(&path).unwrap();
+        let image = image::open(&path).unwrap();
+        let _ = std::fs::remove_file(&path);
+
+        assert_eq!(image::ColorType::L16, image.color());
+        assert_eq!((6, 4), (image.width(), image.height()));
+
+        let image = image.into_luma16();
+        assert_eq!(0, image.get_pixel(0, 0).0[0]);
+        assert_eq!(u16::MAX, image.get_pixel(1, 0).0[0]);
+        assert_eq!(u16::MAX, image.get_pixel(5, 3).0[0]);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn rle_round_trip() {
+        let mut map = NoiseMap::from_fn(32, 16, |x, y| ((x / 8 + y / 4) % 3) as f64 * 0.5 - 0.5);
+        map[(3, 3)] = f64::NAN;
+        let path = std::env::temp_dir().join("noise_map_rle_round_trip.rle");
+
+        map.write_to_rle(&path).unwrap();
+        let encoded_len = std::fs::metadata(&path).unwrap().len() as usize;
+        let read = NoiseMap::read_from_rle(&path).unwrap();
+        let _ = std::fs::remove_file(&path);
+
+        assert_eq!(map.size(), read.size());
+        assert!(map
+            .iter()
+            .zip(read.iter())
+            .all(|(a, b)| a.to_bits() == b.to_bits()));
+        assert!(encoded_len < 32 * 16 * 8);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn rle_rejects_oversized_header_with_truncated_body() {
+        let path = std::env::temp_dir().join("noise_map_rle_truncated.rle");
+        let mut bytes = RLE_MAGIC.to_vec();
+        bytes.extend_from_slice(&32000u32.to_le_bytes());
+        bytes.extend_from_slice(&32000u32.to_le_bytes());
+        bytes.extend_from_slice(&5u32.to_le_bytes());
+        bytes.extend_from_slice(&1.0f64.to_bits().to_le_bytes()[..3]);
+
+        std::fs::write(&path, &bytes).unwrap();
+        let result = NoiseMap::read_from_rle(&path);
+        let _ = std::fs::remove_file(&path);
+
+        assert!(matches!(result, Err(error) if error.kind() == io::ErrorKind::InvalidData));
+    }
+
+    #[test]
+    fn invert_negates_values() {
+        let map = ramp(4, 3);
+        let mut inverted = map.inverted();
+
+        assert_eq!(1.0, inverted[(0, 0)]);
+        assert_eq!(-1.0, inverted[(3, 2)]);
+
+        inverted.invert();
+        assert_eq!(
+            map.iter().collect::<Vec<_>>(),
+            inverted.iter().collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn select_uses_control_regions() {
+        // Control values run from -1.0 to 1.0 in steps of 0.25.
+        let control = ramp(9, 1);
+        let low = NoiseMap::from_fn(9, 1, |_, _| -5.0);
+        let high = NoiseMap::from_fn(9, 1, |_, _| 5.0);
+
+        let linear = control.select(&low, &high, (-0.5, 0.5), 0.0);
+        let expected = [-5.0, -5.0, -5.0, -2.5, 0.0, 2.5, 5.0, 5.0, 5.0];
+        for (x, value) in expected.iter().enumerate() {
+            assert!((value - linear[(x, 0)]).abs() < 1e-12);
+        }
+
+        let hard = control.select(&low, &high, (0.0, 0.0), 0.0);
+        let expected = [-5.0, -5.0, -5.0, -5.0, 5.0, 5.0, 5.0, 5.0, 5.0];
+        for (x, value) in expected.iter().enumerate() {
+            assert_eq!(*value, hard[(x, 0)]);
+        }
+
+        // A falloff of 0.25 eases the ends of the transition, which starts exactly at -0.5.
+        let soft = control.select(&low, &high, (-0.5, 0.5), 0.25);
+        let expected = [
+            -5.0,
+            -5.0,
+            -5.0,
+            -5.0 + 10.0 / 6.0,
+            0.0,
+            5.0 - 10.0 / 6.0,
+            5.0,
+            5.0,
+            5.0,
+        ];
+        for (x, value) in expected.iter().enumerate() {
+            assert!((value - soft[(x, 0)]).abs() < 1e-12);
+        }
+
+        let just_below = NoiseMap::from_fn(1, 1, |_, _| -0.5 - 1e-9);
+        let single = |value| NoiseMap::from_fn(1, 1, move |_, _| value);
+        for falloff in [0.0, 0.25, 10.0] {
+            let selected = just_below.select(&single(-5.0), &single(5.0), (-0.5, 0.5), falloff);
+            assert_eq!(-5.0, selected[(0, 0)]);
+        }
+    }
+
+    #[cfg(feature = "tiff")]
+    #[test]
+    fn write_to_tiff_round_trip() {
+        let map = ramp(6, 4);
+        let path = std::env::temp_dir().join("noise_map_write_to_tiff.tiff");
+
+        map.write_to_tiff(&path).unwrap();
+
+        let mut decoder = tiff::decoder::Decoder::new(File::open(&path).unwrap()).unwrap();
+        assert_eq!((6, 4), decoder.dimensions().unwrap());
+
+        match decoder.read_image().unwrap() {
+            tiff::decoder::DecodingResult::F32(pixels) => {
+                assert_eq!(24, pixels.len());
+                assert_eq!(map[(2, 3)] as f32, pixels[3 * 6 + 2]);
+            }
+            _ => panic!("expected 32-bit float samples"),
+        }
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn laplacian_of_bump_is_negative() {
+        let bump = NoiseMap::from_fn(9, 9, |x, y| {
+            let (dx, dy) = (x as f64 - 4.0, y as f64 - 4.0);
+            1.0 - 0.01 * (dx * dx + dy * dy)
+        });
+        let laplacian = bump.laplacian();
+
+        for y in 1..8 {
+            for x in 1..8 {
+                assert!((laplacian[(x, y)] + 0.04).abs() < 1e-12);
+            }
+        }
+
+        assert!(bump.inverted().laplacian()[(4, 4)] > 0.0);
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn par_rows_mut_visits_each_row() {
+        let original = ramp(5, 6);
+        let mut map = original.clone();
+
+        map.par_rows_mut(|y, row| {
+            assert_eq!(5, row.len());
+
+            if y % 2 == 0 {
+                row.iter_mut().for_each(|value| *value = 0.0);
+            }
+        });
+
+        for y in 0..6 {
+            for x in 0..5 {
+                let expected = if y % 2 == 0 { 0.0 } else { original[(x, y)] };
+                assert_eq!(expected, map[(x, y)]);
+            }
+        }
+    }
+
+    #[test]
+    fn gamma_brightens_midtones() {
+        let map = ramp(9, 1);
+
+        let identity = map.gamma(1.0);
+        assert!(map
+            .iter()
+            .zip(identity.iter())
+            .all(|(a, b)| (a - b).abs() < 1e-12));
+
+        let brightened = map.gamma(2.2);
+        assert_eq!(-1.0, brightened[(0, 0)]);
+        assert_eq!(1.0, brightened[(8, 0)]);
+        assert!((1..8).all(|x| brightened[(x, 0)] > map[(x, 0)]));
+    }
+
+    #[test]
+    fn blend_mode_screen_and_multiply() {
+        // Normalized values: base 0.5 and 0.25, blend 0.5 and 0.0.
+        let base = NoiseMap::from_fn(2, 1, |x, _| [0.0, -0.5][x]);
+        let blend = NoiseMap::from_fn(2, 1, |x, _| [0.0, -1.0][x]);
+
+        let multiplied = base.blend_mode(&blend, BlendMode::Multiply);
+        assert_eq!(-0.5, multiplied[(0, 0)]);
+        assert_eq!(-1.0, multiplied[(1, 0)]);
+
+        let screened = base.blend_mode(&blend, BlendMode::Screen);
+        assert_eq!(0.5, screened[(0, 0)]);
+        assert_eq!(-0.5, screened[(1, 0)]);
+
+        let difference = base.blend_mode(&blend, BlendMode::Difference);
+        assert_eq!(-1.0, difference[(0, 0)]);
+        assert_eq!(-0.5, difference[(1, 0)]);
+    }
+
+    #[test]
+    fn mip_chain_halves_to_one_cell() {
+        let chain = ramp(16, 5).mip_chain();
+
+        let sizes: Vec<_> = chain.iter().map(NoiseMap::size).collect();
+        assert_eq!(vec![(16, 5), (8, 2), (4, 1), (2, 1), (1, 1)], sizes);
+
+        let flat = NoiseMap::from_fn(8, 8, |_, _| 0.25).mipmap();
+        assert_eq!((4, 4), flat.size());
+        assert!(flat.iter().all(|value| (value - 0.25).abs() < 1e-12));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn csv_round_trip() {
+        let map = NoiseMap::from_fn(3, 3, |x, y| (x as f64 - 1.0) / 3.0 + y as f64 * 0.1);
+        let path = std::env::temp_dir().join("noise_map_csv_round_trip.csv");
+
+        map.write_to_csv(&path).unwrap();
+        let read = NoiseMap::read_from_csv(&path).unwrap();
+
+        assert_eq!((3, 3), read.size());
+        assert!(map.iter().eq(read.iter()));
+
+        std::fs::write(&path, "0.5,0.25\n1.0\n").unwrap();
+        let error = NoiseMap::read_from_csv(&path).err().unwrap();
+        assert_eq!(io::ErrorKind::InvalidData, error.kind());
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn argmax_and_argmin_locate_extrema() {
+        let mut map = NoiseMap::from_fn(5, 4, |_, _| 0.0);
+        map[(3, 2)] = 2.0;
+        map[(0, 0)] = f64::NAN;
+        map[(1, 3)] = -1.0;
+        map[(4, 3)] = -1.0;
+
+        assert_eq!(Some((3, 2)), map.argmax());
+        assert_eq!(Some((1, 3)), map.argmin());
+
+        assert_eq!(None, NoiseMap::new(0, 0).argmax());
+        assert_eq!(None, NoiseMap::from_fn(2, 2, |_, _| f64::NAN).argmin());
+    }
+
+    #[test]
+    fn as_slice_is_row_major() {
+        let map = ramp(4, 3);
+        let slice = map.as_slice();
+
+        assert_eq!(12, slice.len());
+        assert!(slice.iter().eq(map.iter()));
+        assert_eq!(map[(1, 2)], slice[2 * 4 + 1]);
+
+        let floats = map.to_f32_vec();
+        assert_eq!(12, floats.len());
+        assert_eq!(-1.0f32, floats[0]);
+        assert_eq!(1.0f32, floats[11]);
+    }
+
+    #[test]
+    fn label_regions_separates_blobs() {
+        let mut map = NoiseMap::from_fn(6, 5, |_, _| -1.0);
+        for (x, y) in [(0, 0), (1, 0), (1, 1), (4, 3), (5, 3), (4, 4)] {
+            map[(x, y)] = 1.0;
+        }
+        // Diagonal neighbors are not connected.
+        map[(2, 2)] = 1.0;
+
+        let (labels, count) = map.label_regions(0.0);
+        let label = |x: usize, y: usize| labels[y * 6 + x];
+
+        assert_eq!(3, count);
+        assert_eq!(1, label(0, 0));
+        assert_eq!(1, label(1, 1));
+        assert_eq!(2, label(2, 2));
+        assert_eq!(3, label(4, 4));
+        assert_eq!(label(4, 3), label(5, 3));
+        assert_eq!(0, label(3, 0));
+    }
+
+    #[test]
+    fn weighted_blend_uses_complementary_weights() {
+        let low = NoiseMap::from_fn(4, 1, |_, _| -1.0);
+        let high = NoiseMap::from_fn(4, 1, |_, _| 1.0);
+        let high_weight = NoiseMap::from_fn(4, 1, |x, _| x as f64 / 3.0);
+        let low_weight = high_weight.map(|weight| 1.0 - weight);
+
+        let blended = weighted_blend(&[(&low, &low_weight), (&high, &high_weight)]);
+        for x in 0..4 {
+            assert!((blended[(x, 0)] - (2.0 * x as f64 / 3.0 - 1.0)).abs() < 1e-12);
+        }
+
+        let zero = NoiseMap::from_fn(4, 1, |_, _| 0.0);
+        assert_eq!(0.0, weighted_blend(&[(&high, &zero)])[(2, 0)]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn weighted_blend_rejects_mismatched_weights() {
+        let _ = weighted_blend(&[(&NoiseMap::new(4, 1), &NoiseMap::new(2, 2))]);
+    }
+
+    #[test]
+    fn iter_morton_follows_z_order() {
+        let map = ramp(4, 4);
+        let coordinates: Vec<_> = map.iter_morton().map(|(x, y, _)| (x, y)).collect();
+
+        assert_eq!(16, coordinates.len());
+        assert_eq!(
+            [
+                (0, 0),
+                (1, 0),
+                (0, 1),
+                (1, 1),
+                (2, 0),
+                (3, 0),
+                (2, 1),
+                (3, 1),
+                (0, 2)
+            ],
+            coordinates[..9]
+        );
+        assert!(map.iter_morton().all(|(x, y, value)| value == map[(x, y)]));
+
+        // Cells outside a 3x2 map are skipped on the enclosing 4x4 curve.
+        let uneven: Vec<_> = ramp(3, 2).iter_morton().map(|(x, y, _)| (x, y)).collect();
+        assert_eq!(vec![(0, 0), (1, 0), (0, 1), (1, 1), (2, 0), (2, 1)], uneven);
+    }
+
+    #[test]
+    fn thumbnail_preserves_aspect_ratio() {
+        let map = NoiseMap::from_fn(200, 100, |x, _| x as f64 / 199.0);
+
+        let thumbnail = map.thumbnail(50);
+        assert_eq!((50, 25), thumbnail.size());
+        assert!((0..49).all(|x| thumbnail[(x, 10)] < thumbnail[(x + 1, 10)]));
+
+        let small = ramp(20, 10);
+        assert!(small.thumbnail(50).iter().eq(small.iter()));
+    }
+
+    #[test]
+    fn feathered_mask_fades_in_over_feather_width() {
+        let map = NoiseMap::from_fn(10, 3, |_, _| 0.8);
+        let mask = NoiseMap::from_fn(10, 3, |x, _| if x >= 2 { 1.0 } else { -1.0 });
+
+        let feathered = map.feathered_mask(&mask, 4);
+        let expected = [0.0, 0.0, 0.2, 0.4, 0.6, 0.8, 0.8, 0.8, 0.8, 0.8];
+        for (x, value) in expected.iter().enumerate() {
+            assert!((feathered[(x, 1)] - value).abs() < 1e-12);
+        }
+
+        let hard = map.feathered_mask(&mask, 0);
+        assert_eq!(0.0, hard[(1, 0)]);
+        assert_eq!(0.8, hard[(2, 0)]);
+    }
+
+    #[test]
+    fn compose_rgb_interleaves_channels() {
+        let constant = |value| NoiseMap::from_fn(3, 2, move |_, _| value);
+
+        let pixels = compose_rgb(&constant(1.0), &constant(-1.0), &constant(0.0));
+        assert_eq!(18, pixels.len());
+        assert!(pixels.chunks(3).all(|pixel| pixel == [255, 0, 127]));
+    }
+
+    #[test]
+    #[should_panic]
+    fn compose_rgb_rejects_mismatched_channels() {
+        let _ = compose_rgb(
+            &NoiseMap::new(3, 2),
+            &NoiseMap::new(3, 2),
+            &NoiseMap::new(2, 2),
+        );
+    }
+
+    #[test]
+    fn region_minmax_covers_only_the_region() {
+        let map = ramp(6, 5);
+        let (min, max) = map.region_minmax(1, 2, 3, 2).unwrap();
+
+        let mut expected_min = f64::INFINITY;
+        let mut expected_max = f64::NEG_INFINITY;
+        for y in 2..4 {
+            for x in 1..4 {
+                expected_min = expected_min.min(map[(x, y)]);
+                expected_max = expected_max.max(map[(x, y)]);
+            }
+        }
+
+        assert_eq!((expected_min, expected_max), (min, max));
+        assert_eq!(
+            Some((map[(5, 4)], map[(5, 4)])),
+            map.region_minmax(5, 4, 1, 1)
+        );
+        assert_eq!(None, map.region_minmax(4, 0, 3, 1));
+        assert_eq!(None, map.region_minmax(0, 4, 1, 2));
+        assert_eq!(None, map.region_minmax(2, 2, 0, 2));
+    }
+
+    #[cfg(feature = "fft")]
+    #[test]
+    fn dominant_frequency_finds_sine_frequency() {
+        use core::f64::consts::TAU;
+
+        let grid = NoiseMap::from_fn(32, 16, |x, y| {
+            (TAU * 3.0 * x as f64 / 32.0).sin() * (TAU * 5.0 * y as f64 / 16.0).cos()
+        });
+        assert_eq!((3.0 / 32.0, 5.0 / 16.0), grid.dominant_frequency());
+
+        let stripes = NoiseMap::from_fn(32, 8, |x, _| 0.5 + (TAU * 4.0 * x as f64 / 32.0).sin());
+        assert_eq!((4.0 / 32.0, 0.0), stripes.dominant_frequency());
+
+        assert_eq!((0.0, 0.0), NoiseMap::new(1, 1).dominant_frequency());
+    }
+
+    #[test]
+    fn discontinuities_flag_both_sides_of_a_step() {
+        let mut map = NoiseMap::from_fn(5, 3, |x, y| if x < 3 { 0.1 * y as f64 } else { 2.0 });
+        map[(0, 2)] = f64::NAN;
+
+        let flagged = map.discontinuities(0.5);
+
+        let expected = vec![
+            (2, 0),
+            (3, 0),
+            (0, 1),
+            (2, 1),
+            (3, 1),
+            (0, 2),
+            (1, 2),
+            (2, 2),
+            (3, 2),
+        ];
+
+        assert_eq!(expected, flagged);
+        assert!(NoiseMap::from_fn(4, 4, |x, _| x as f64)
+            .discontinuities(1.0)
+            .is_empty());
+    }
+
+    #[test]
+    fn first_above_along_finds_crossing_cell() {
+        let map = NoiseMap::from_fn(8, 6, |x, y| x as f64 + 0.5 * y as f64);
+
+        assert_eq!(Some((5, 2)), map.first_above_along((0, 2), (1, 0), 5.5));
+        assert_eq!(Some((4, 4)), map.first_above_along((1, 1), (1, 1), 5.5));
+        assert_eq!(Some((2, 0)), map.first_above_along((2, 0), (-1, 0), 1.0));
+        assert_eq!(None, map.first_above_along((7, 5), (-1, -1), 10.0));
+        assert_eq!(None, map.first_above_along((0, 0), (0, 0), 1.0));
+        assert_eq!(None, map.first_above_along((8, 0), (1, 0), -1.0));
+    }
+
+    #[test]
+    fn texture_data_remaps_to_unit_range() {
+        let mut map = ramp(3, 2);
+        map[(2, 1)] = 4.0;
+
+        let (width, height, texels) = map.to_texture_data();
+
+        assert_eq!((3, 2), (width, height));
+        assert_eq!(vec![0.0, 0.2, 0.4, 0.6, 0.8, 1.0], texels);
+    }
+
+    #[test]
+    fn kaleidoscope_mirrors_wedges() {
+        let map = NoiseMap::from_fn(9, 7, |x, y| ((x * 7 + y * 13) % 11) as f64);
+
+        assert!(map.kaleidoscope(1).iter().eq(map.iter()));
+
+        let mirrored = map.kaleidoscope(2);
+        for y in 0..7 {
+            for x in 0..9 {
+                assert!((mirrored[(x, y)] - mirrored[(8 - x, y)]).abs() < 1e-9);
+            }
+            for x in 4..9 {
+                assert!((mirrored[(x, y)] - map[(x, y)]).abs() < 1e-9);
+            }
+        }
+
+        let six = map.kaleidoscope(6);
+        assert!((six[(4, 3)] - map[(4, 3)]).abs() < 1e-9);
+    }
+
+    #[test]
+    fn curve_through_identity_points_reproduces_input() {
+        let map = ramp(5, 4);
+
+        let identity = map.curve(&[(1.0, 1.0), (-1.0, -1.0), (0.25, 0.25), (0.0, 0.0)]);
+        assert!(map
+            .iter()
+            .zip(identity.iter())
+            .all(|(a, b)| (a - b).abs() < 1e-12));
+
+        let tone = map.curve(&[(-0.5, 0.0), (0.0, 0.1), (0.5, 1.0)]);
+        assert_eq!(0.0, tone[(0, 0)]);
+        assert_eq!(1.0, tone[(4, 3)]);
+        assert!(tone.iter().zip(tone.iter().skip(1)).all(|(a, b)| a <= b));
+
+        assert!(map.curve(&[]).iter().eq(map.iter()));
+        assert!(map.curve(&[(0.0, 0.3)]).iter().all(|&value| value == 0.3));
+    }
+
+    #[test]
+    fn autocontrast_clamps_outliers() {
+        let mut map = NoiseMap::from_fn(10, 10, |x, y| (x + y * 10) as f64 * 0.001);
+        map[(0, 0)] = -50.0;
+        map[(9, 9)] = 50.0;
+
+        let pixels = map.to_grayscale_autocontrast(2.0, 98.0);
+
+        assert_eq!(100, pixels.len());
+        assert_eq!(0, pixels[0]);
+        assert_eq!(0, pixels[1]);
+        assert_eq!(255, pixels[98]);
+        assert_eq!(255, pixels[99]);
+        assert!(pixels[2] < 5);
+        assert!(pixels[97] > 250);
+
+        let flat = NoiseMap::from_fn(3, 3, |_, _| 0.5);
+        assert!(flat
+            .to_grayscale_autocontrast(2.0, 98.0)
+            .iter()
+            .all(|&p| p == 0));
+    }
+
+    #[test]
+    fn bilateral_blur_keeps_edges_and_smooths_noise() {
+        let jitter = |x: usize, y: usize| ((x * 7 + y * 3) % 5) as f64 * 0.02 - 0.04;
+        let map = NoiseMap::from_fn(16, 8, |x, y| {
+            let level = if x < 8 { -1.0 } else { 1.0 };
+            level + jitter(x, y)
+        });
+
+        let blurred = map.bilateral_blur(1.5, 0.2);
+        let boxed = map.box_blur(2);
+
+        for y in 0..8 {
+            assert!((blurred[(7, y)] + 1.0).abs() < 0.05);
+            assert!((blurred[(8, y)] - 1.0).abs() < 0.05);
+            assert!((boxed[(8, y)] - 1.0).abs() > 0.3);
+        }
+
+        let spread = |map: &NoiseMap| {
+            let values = (0..6).flat_map(|y| (1..6).map(move |x| (x, y + 1)));
+            let (min, max) = values.fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), cell| {
+                (min.min(map[cell]), max.max(map[cell]))
+            });
+            max - min
+        };
+        assert!(spread(&blurred) < 0.5 * spread(&map));
+    }
+
+    #[test]
+    fn bilateral_blur_with_zero_sigma_is_identity() {
+        let map = NoiseMap::from_fn(6, 5, |x, y| ((x * 7 + y * 3) % 5) as f64 * 0.2 - 0.4);
+
+        assert!(map.bilateral_blur(0.0, 0.2).iter().eq(map.iter()));
+        assert!(map.bilateral_blur(1.5, 0.0).iter().eq(map.iter()));
+    }
+
+    #[test]
+    fn count_islands_respects_connectivity() {
+        let land = [(1, 1), (2, 1), (1, 2), (5, 4), (6, 4), (4, 5), (6, 5)];
+        let mut map = NoiseMap::from_fn(8, 7, |_, _| -0.5);
+        for &cell in &land {
+            map[cell] = 0.4;
+        }
+
+        assert_eq!(3, map.count_islands(0.0));
+        assert_eq!(2, map.count_islands_with(0.0, Connectivity::Eight));
+        assert_eq!(0, map.count_islands(0.5));
+
+        map[(4, 5)] = -0.5;
+        assert_eq!(2, map.count_islands(0.0));
+    }
+
+    #[test]
+    fn palette_indices_pick_nearest_entry() {
+        let map = NoiseMap::from_fn(5, 1, |x, _| x as f64 * 0.5 - 1.0);
+
+        assert_eq!(vec![0, 0, 0, 1, 1], map.to_palette_indices(&[-0.5, 0.5]));
+        assert_eq!(vec![1, 1, 0, 0, 0], map.to_palette_indices(&[0.25, -1.0]));
+    }
+
+    #[test]
+    #[should_panic]
+    fn palette_indices_reject_empty_palette() {
+        NoiseMap::new(2, 2).to_palette_indices(&[]);
+    }
+
+    #[test]
+    fn swirl_rotates_more_with_radius() {
+        let x_ramp = NoiseMap::from_fn(21, 21, |x, _| x as f64);
+        let y_ramp = NoiseMap::from_fn(21, 21, |_, y| y as f64);
+
+        assert!(x_ramp
+            .swirl(0.0)
+            .iter()
+            .zip(x_ramp.iter())
+            .all(|(a, b)| (a - b).abs() < 1e-12));
+
+        let (swirled_x, swirled_y) = (x_ramp.swirl(0.05), y_ramp.swirl(0.05));
+        let displacement = |radius: usize| {
+            let cell = (10 + radius, 10);
+            let dx = swirled_x[cell] - x_ramp[cell];
+            let dy = swirled_y[cell] - y_ramp[cell];
+
+            assert!(radius == 0 || dy > 0.0);
+            (dx * dx + dy * dy).sqrt()
+        };
+
+        assert!(displacement(0) < 1e-12);
+        assert!(displacement(2) < displacement(5));
+        assert!(displacement(5) < displacement(8));
+    }
+
+    #[test]
+    fn ssim_is_one_for_identical_maps_and_high_for_blur() {
+        let map = NoiseMap::from_fn(32, 32, |x, y| {
+            (x as f64 * 0.7).sin() * 0.6 + (y as f64 * 0.45 + x as f64 * 0.2).cos() * 0.3
+        });
+
+        assert_eq!(1.0, map.ssim(&map, 7));
+
+        let blurred = map.ssim(&map.box_blur(1), 7);
+        assert!(blurred < 1.0);
+        assert!(blurred > 0.8);
+    }
+
+    #[test]
+    fn hillshade_lights_slopes_facing_the_sun() {
+        let flat = NoiseMap::from_fn(4, 4, |_, _| 0.3).hillshade(315.0, 45.0, 1.0);
+        let expected = 45.0_f64.to_radians().sin();
+        assert!(flat.iter().all(|value| (value - expected).abs() < 1e-12));
+
+        // Rising towards +x, so the slope faces west, towards a light in the west.
+        let slope = NoiseMap::from_fn(6, 6, |x, _| x as f64 * 0.5);
+        let lit = slope.hillshade(270.0, 30.0, 1.0);
+        let shaded = slope.hillshade(90.0, 30.0, 1.0);
+
+        assert!(lit[(2, 2)] > shaded[(2, 2)]);
+        assert!(lit[(2, 2)] > 30.0_f64.to_radians().sin());
+    }
+
+    #[test]
+    fn masks_replace_only_cells_past_level() {
+        let map = ramp(5, 1);
+
+        assert!(map
+            .mask_below(0.0, -9.0)
+            .iter()
+            .eq([-9.0, -9.0, 0.0, 0.5, 1.0].iter()));
+        assert!(map
+            .mask_above(0.0, 9.0)
+            .iter()
+            .eq([-1.0, -0.5, 0.0, 9.0, 9.0].iter()));
+    }
+
+    #[test]
+    fn mesh_shares_vertices_between_triangles() {
+        let map = ramp(4, 3);
+
+        let (positions, indices) = map.to_mesh(2.0);
+
+        assert_eq!(4 * 3, positions.len());
+        assert_eq!((4 - 1) * (3 - 1) * 6, indices.len());
+        assert!(indices
+            .iter()
+            .all(|&index| (index as usize) < positions.len()));
+        assert_eq!([2.0, (map[(2, 1)] * 2.0) as f32, 1.0], positions[4 + 2]);
+        assert_eq!(&[0, 4, 1, 1, 4, 5], &indices[..6]);
+
+        let (positions, indices) = NoiseMap::new(5, 1).to_mesh(1.0);
+        assert_eq!(5, positions.len());
+        assert!(indices.is_empty());
     }
 }
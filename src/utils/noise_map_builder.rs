@@ -24,12 +24,110 @@ pub trait NoiseMapBuilder<SourceModule> {
     fn size(&self) -> (usize, usize);
 
     fn build(&self) -> NoiseMap;
+
+    /// Samples the source module once and stores the raw result in a [`SampleCache`].
+    ///
+    /// The default implementation stores the result of [`build`](Self::build). Builders with
+    /// post-processing settings store the samples before post-processing instead.
+    fn build_cache(&self) -> SampleCache {
+        SampleCache { map: self.build() }
+    }
+
+    /// Builds a map from previously cached samples without re-sampling the source module,
+    /// applying this builder's post-processing to them.
+    ///
+    /// The default implementation returns the cached samples unchanged.
+    fn build_from_cache(&self, cache: &SampleCache) -> NoiseMap {
+        cache.map.clone()
+    }
+
+    /// Builds the raw map, then returns a second map of the same size where each cell is
+    /// `f(raw_map, x, y)`.
+    ///
+    /// This gives post-processing effects access to every cell's neighbors, which are not yet
+    /// available while the raw map is being sampled.
+    fn build_then<F>(&self, f: F) -> NoiseMap
+    where
+        F: Fn(&NoiseMap, usize, usize) -> f64,
+        Self: Sized,
+    {
+        let raw_map = self.build();
+        let (width, height) = raw_map.size();
+
+        NoiseMap::from_fn(width, height, |x, y| f(&raw_map, x, y))
+            .set_border_value(raw_map.border_value())
+    }
 }
 
+/// Raw samples produced by [`NoiseMapBuilder::build_cache`], reusable across builds.
+#[derive(Clone)]
+pub struct SampleCache {
+    map: NoiseMap,
+}
+
+impl SampleCache {
+    pub fn map(&self) -> &NoiseMap {
+        &self.map
+    }
+}
+
+mod chunked_plane;
 mod cylinder_map;
+mod dyn_plane_map;
+mod ico_sphere;
+mod nearest_source;
 mod plane_map;
 mod sphere_map;
+mod wang_tile;
 
+pub use chunked_plane::*;
 pub use cylinder_map::*;
+pub use dyn_plane_map::*;
+pub use ico_sphere::*;
+pub use nearest_source::*;
 pub use plane_map::*;
 pub use sphere_map::*;
+pub use wang_tile::*;
+
+use plane_map::plane_point;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Perlin;
+
+    #[test]
+    fn cached_build_matches_direct_build() {
+        let builder = PlaneMapBuilder::new(Perlin::new(7)).set_size(16, 16);
+
+        let cache = builder.build_cache();
+        let direct = builder.build();
+
+        assert!(builder.build_from_cache(&cache).iter().eq(direct.iter()));
+        assert!(cache.map().iter().eq(direct.iter()));
+    }
+
+    #[test]
+    fn post_pass_sees_neighbors() {
+        let builder = PlaneMapBuilder::new(Perlin::new(7)).set_size(12, 10);
+
+        let averaged = builder.build_then(|raw, x, y| {
+            let rows = y.saturating_sub(1)..(y + 2).min(10);
+            let cells: alloc::vec::Vec<f64> = rows
+                .flat_map(|sy| (x.saturating_sub(1)..(x + 2).min(12)).map(move |sx| raw[(sx, sy)]))
+                .collect();
+
+            cells.iter().sum::<f64>() / cells.len() as f64
+        });
+
+        let raw = builder.build();
+        let expected = (0..3)
+            .flat_map(|sy| (4..7).map(move |sx| (sx, sy)))
+            .map(|cell| raw[cell])
+            .sum::<f64>()
+            / 9.0;
+
+        assert_eq!((12, 10), averaged.size());
+        assert!((averaged[(5, 1)] - expected).abs() < 1e-12);
+    }
+}
@@ -0,0 +1,117 @@
+use crate::{utils::NoiseMap, NoiseFn};
+
+/// Builds square chunks of an unbounded plane on a single global sampling grid, for terrain
+/// that is generated piece by piece.
+///
+/// Each chunk is `chunk_size` cells across and holds `chunk_size + 1` samples per side, so
+/// adjacent chunks share their border row and column, as neighboring terrain meshes share
+/// their edge vertices. Global sample `(i, j)` lies at `(i * cell_size, j * cell_size, 0.0)`.
+pub struct ChunkedPlaneBuilder<SourceModule>
+where
+    SourceModule: NoiseFn<f64, 3>,
+{
+    chunk_size: usize,
+    cell_size: f64,
+    source_module: SourceModule,
+}
+
+impl<SourceModule> ChunkedPlaneBuilder<SourceModule>
+where
+    SourceModule: NoiseFn<f64, 3>,
+{
+    pub fn new(source_module: SourceModule) -> Self {
+        ChunkedPlaneBuilder {
+            chunk_size: 32,
+            cell_size: 1.0 / 32.0,
+            source_module,
+        }
+    }
+
+    pub fn set_chunk_size(self, chunk_size: usize) -> Self {
+        ChunkedPlaneBuilder { chunk_size, ..self }
+    }
+
+    /// Sets the world-space distance between neighboring samples.
+    pub fn set_cell_size(self, cell_size: f64) -> Self {
+        ChunkedPlaneBuilder { cell_size, ..self }
+    }
+
+    pub fn chunk_size(&self) -> usize {
+        self.chunk_size
+    }
+
+    pub fn cell_size(&self) -> f64 {
+        self.cell_size
+    }
+
+    /// Builds chunk `(cx, cy)`, a map of `chunk_size + 1` samples per side.
+    pub fn build_chunk(&self, cx: i64, cy: i64) -> NoiseMap {
+        self.build_region(cx, cy, 0)
+    }
+
+    /// Builds chunk `(cx, cy)` together with a skirt of one extra sample on every side.
+    ///
+    /// The skirt gives every edge sample of the chunk its true neighbors, so slopes and normals
+    /// computed with central differences match across chunk boundaries. Crop the skirt off by
+    /// dropping the outermost row and column on each side.
+    pub fn build_chunk_with_skirt(&self, cx: i64, cy: i64) -> NoiseMap {
+        self.build_region(cx, cy, 1)
+    }
+
+    fn build_region(&self, cx: i64, cy: i64, skirt: usize) -> NoiseMap {
+        let size = self.chunk_size + 1 + 2 * skirt;
+        let origin_x = cx * self.chunk_size as i64 - skirt as i64;
+        let origin_y = cy * self.chunk_size as i64 - skirt as i64;
+
+        NoiseMap::from_fn(size, size, |x, y| {
+            let global_x = origin_x + x as i64;
+            let global_y = origin_y + y as i64;
+
+            self.source_module.get([
+                global_x as f64 * self.cell_size,
+                global_y as f64 * self.cell_size,
+                0.0,
+            ])
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Perlin;
+
+    /// Returns the unnormalized normal at `(x, y)` from central differences.
+    fn normal(map: &NoiseMap, x: usize, y: usize) -> [f64; 3] {
+        [
+            map[(x - 1, y)] - map[(x + 1, y)],
+            map[(x, y - 1)] - map[(x, y + 1)],
+            2.0,
+        ]
+    }
+
+    #[test]
+    fn skirt_normals_match_across_chunk_boundary() {
+        let builder = ChunkedPlaneBuilder::new(Perlin::new(43))
+            .set_chunk_size(8)
+            .set_cell_size(0.15);
+
+        let left = builder.build_chunk_with_skirt(0, 0);
+        let right = builder.build_chunk_with_skirt(1, 0);
+
+        assert_eq!((11, 11), left.size());
+
+        // The chunk occupies skirt columns 1..=9, so the left chunk's right edge is column 9 and
+        // the right chunk's left edge is column 1.
+        for y in 1..10 {
+            assert_eq!(normal(&left, 9, y), normal(&right, 1, y));
+        }
+
+        let chunk = builder.build_chunk(1, 0);
+        for y in 0..9 {
+            for x in 0..9 {
+                assert_eq!(chunk[(x, y)], right[(x + 1, y + 1)]);
+            }
+        }
+    }
+}
@@ -1,4 +1,5 @@
 use crate::{utils::NoiseMap, NoiseFn};
+use alloc::boxed::Box;
 
 use super::NoiseMapBuilder;
 
@@ -9,9 +10,12 @@ where
     angle_bounds: (f64, f64),
     height_bounds: (f64, f64),
     size: (usize, usize),
+    angle_distribution: Option<AngleDistribution>,
     source_module: SourceModule,
 }
 
+type AngleDistribution = Box<dyn Fn(f64) -> f64 + Send + Sync>;
+
 impl<SourceModule> CylinderMapBuilder<SourceModule>
 where
     SourceModule: NoiseFn<f64, 3>,
@@ -21,6 +25,7 @@ where
             angle_bounds: (-90.0, 90.0),
             height_bounds: (-1.0, 1.0),
             size: (100, 100),
+            angle_distribution: None,
             source_module,
         }
     }
@@ -51,6 +56,23 @@ where
         }
     }
 
+    /// Remaps the position of each column through `distribution` before it is converted to an
+    /// angle.
+    ///
+    /// `distribution` receives the column's fraction of the map width, from 0.0 at the first
+    /// column towards 1.0, and should return a fraction in the same range. The identity function
+    /// reproduces the default, evenly spaced columns, while curves with a shallow slope pack
+    /// more columns into the corresponding part of the angle bounds.
+    pub fn set_angle_distribution<F>(self, distribution: F) -> Self
+    where
+        F: Fn(f64) -> f64 + Send + Sync + 'static,
+    {
+        CylinderMapBuilder {
+            angle_distribution: Some(Box::new(distribution)),
+            ..self
+        }
+    }
+
     pub fn angle_bounds(&self) -> (f64, f64) {
         self.angle_bounds
     }
@@ -97,7 +119,12 @@ where
             let current_height = self.height_bounds.0 + y_step * y as f64;
 
             for x in 0..width {
-                let current_angle = self.angle_bounds.0 + x_step * x as f64;
+                let current_angle = match &self.angle_distribution {
+                    Some(distribution) => {
+                        self.angle_bounds.0 + angle_extent * distribution(x as f64 / width as f64)
+                    }
+                    None => self.angle_bounds.0 + x_step * x as f64,
+                };
 
                 let point_x = current_angle.to_radians().cos();
                 let point_z = current_angle.to_radians().sin();
@@ -111,3 +138,41 @@ where
         result_map
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns the angle of the sampled point around the cylinder's axis, in degrees.
+    struct Angle;
+
+    impl NoiseFn<f64, 3> for Angle {
+        fn get(&self, point: [f64; 3]) -> f64 {
+            point[2].atan2(point[0]).to_degrees()
+        }
+    }
+
+    #[test]
+    fn angle_distribution_remaps_columns() {
+        let uniform = CylinderMapBuilder::new(Angle).set_size(8, 1).build();
+
+        let identity = CylinderMapBuilder::new(Angle)
+            .set_size(8, 1)
+            .set_angle_distribution(|t| t)
+            .build();
+        assert!(uniform
+            .iter()
+            .zip(identity.iter())
+            .all(|(a, b)| (a - b).abs() < 1e-9));
+
+        let clustered = CylinderMapBuilder::new(Angle)
+            .set_size(8, 1)
+            .set_angle_distribution(|t| t * t)
+            .build();
+        let first_step = clustered[(1, 0)] - clustered[(0, 0)];
+        let last_step = clustered[(7, 0)] - clustered[(6, 0)];
+
+        assert!((first_step - 180.0 / 64.0).abs() < 1e-9);
+        assert!((last_step - 180.0 * 13.0 / 64.0).abs() < 1e-9);
+    }
+}
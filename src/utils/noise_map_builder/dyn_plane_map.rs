@@ -0,0 +1,153 @@
+use crate::{utils::NoiseMap, DynSampler};
+use alloc::{boxed::Box, vec};
+
+use super::NoiseMapBuilder;
+
+/// Builds a map on the plane from a [`DynSampler`], whose dimension is checked at runtime.
+///
+/// Cells are laid out and sampled as in a non-seamless [`PlaneMapBuilder`](super::PlaneMapBuilder)
+/// of the sampler's dimension: the first two coordinates are the cell's position, the third is
+/// 0.0, and the fourth is 0.5.
+pub struct DynPlaneMapBuilder {
+    x_bounds: (f64, f64),
+    y_bounds: (f64, f64),
+    size: (usize, usize),
+    sampler: Box<dyn DynSampler>,
+}
+
+impl DynPlaneMapBuilder {
+    /// # Panics
+    ///
+    /// Panics if the sampler has fewer than two dimensions.
+    pub fn new(sampler: Box<dyn DynSampler>) -> Self {
+        assert_dim(sampler.as_ref());
+
+        DynPlaneMapBuilder {
+            x_bounds: (-1.0, 1.0),
+            y_bounds: (-1.0, 1.0),
+            size: (100, 100),
+            sampler,
+        }
+    }
+
+    pub fn set_x_bounds(self, lower_x_bound: f64, upper_x_bound: f64) -> Self {
+        DynPlaneMapBuilder {
+            x_bounds: (lower_x_bound, upper_x_bound),
+            ..self
+        }
+    }
+
+    pub fn set_y_bounds(self, lower_y_bound: f64, upper_y_bound: f64) -> Self {
+        DynPlaneMapBuilder {
+            y_bounds: (lower_y_bound, upper_y_bound),
+            ..self
+        }
+    }
+
+    pub fn x_bounds(&self) -> (f64, f64) {
+        self.x_bounds
+    }
+
+    pub fn y_bounds(&self) -> (f64, f64) {
+        self.y_bounds
+    }
+
+    pub fn dim(&self) -> usize {
+        self.sampler.dim()
+    }
+}
+
+impl NoiseMapBuilder<Box<dyn DynSampler>> for DynPlaneMapBuilder {
+    fn set_size(self, width: usize, height: usize) -> Self {
+        DynPlaneMapBuilder {
+            size: (width, height),
+            ..self
+        }
+    }
+
+    /// # Panics
+    ///
+    /// Panics if the sampler has fewer than two dimensions.
+    fn set_source_module(self, sampler: Box<dyn DynSampler>) -> Self {
+        assert_dim(sampler.as_ref());
+
+        DynPlaneMapBuilder { sampler, ..self }
+    }
+
+    fn size(&self) -> (usize, usize) {
+        self.size
+    }
+
+    fn build(&self) -> NoiseMap {
+        let (width, height) = self.size;
+        let dim = self.sampler.dim();
+
+        let x_step = (self.x_bounds.1 - self.x_bounds.0) / width as f64;
+        let y_step = (self.y_bounds.1 - self.y_bounds.0) / height as f64;
+
+        let mut point = vec![0.0; dim];
+        if dim > 3 {
+            point[3] = 0.5;
+        }
+
+        let mut result_map = NoiseMap::new(width, height);
+
+        for y in 0..height {
+            point[1] = self.y_bounds.0 + y_step * y as f64;
+
+            for x in 0..width {
+                point[0] = self.x_bounds.0 + x_step * x as f64;
+
+                result_map[(x, y)] = self.sampler.sample(&point);
+            }
+        }
+
+        result_map
+    }
+}
+
+fn assert_dim(sampler: &dyn DynSampler) {
+    assert!(
+        sampler.dim() >= 2,
+        "plane maps need a sampler of at least two dimensions, got {}",
+        sampler.dim()
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{utils::PlaneMapBuilder, DynNoiseFn, Perlin};
+
+    #[test]
+    fn dyn_sampler_builds_like_plane_map() {
+        let sampler: Box<dyn DynSampler> = Box::new(DynNoiseFn::<_, 3>::new(Perlin::new(67)));
+        let builder = DynPlaneMapBuilder::new(sampler)
+            .set_size(10, 8)
+            .set_x_bounds(-2.0, 1.0)
+            .set_y_bounds(0.5, 2.5);
+
+        let expected = PlaneMapBuilder::new(Perlin::new(67))
+            .set_size(10, 8)
+            .set_x_bounds(-2.0, 1.0)
+            .set_y_bounds(0.5, 2.5)
+            .build();
+
+        assert_eq!(3, builder.dim());
+        assert!(builder.build().iter().eq(expected.iter()));
+    }
+
+    #[test]
+    #[should_panic]
+    fn one_dimensional_samplers_are_rejected() {
+        struct Line;
+
+        impl crate::NoiseFn<f64, 1> for Line {
+            fn get(&self, point: [f64; 1]) -> f64 {
+                point[0]
+            }
+        }
+
+        DynPlaneMapBuilder::new(Box::new(DynNoiseFn::new(Line)));
+    }
+}
@@ -0,0 +1,154 @@
+use crate::NoiseFn;
+use alloc::{collections::BTreeMap, vec::Vec};
+
+/// Samples the source module at the vertices of a subdivided icosahedron projected onto the unit
+/// sphere.
+///
+/// The vertices are spread far more evenly than a latitude/longitude grid, which makes the
+/// output well suited to mesh-based planet rendering. Each subdivision splits every triangle into
+/// four, so level `n` produces `10 * 4^n + 2` vertices.
+pub struct IcoSphereBuilder<SourceModule>
+where
+    SourceModule: NoiseFn<f64, 3>,
+{
+    subdivisions: usize,
+    source_module: SourceModule,
+}
+
+impl<SourceModule> IcoSphereBuilder<SourceModule>
+where
+    SourceModule: NoiseFn<f64, 3>,
+{
+    pub fn new(source_module: SourceModule) -> Self {
+        IcoSphereBuilder {
+            subdivisions: 3,
+            source_module,
+        }
+    }
+
+    pub fn set_subdivisions(self, subdivisions: usize) -> Self {
+        IcoSphereBuilder {
+            subdivisions,
+            ..self
+        }
+    }
+
+    pub fn subdivisions(&self) -> usize {
+        self.subdivisions
+    }
+
+    pub fn set_source_module(self, source_module: SourceModule) -> Self {
+        IcoSphereBuilder {
+            source_module,
+            ..self
+        }
+    }
+
+    /// Returns every vertex of the icosphere paired with the source module's value there.
+    pub fn build(&self) -> Vec<([f64; 3], f64)> {
+        ico_sphere_vertices(self.subdivisions)
+            .into_iter()
+            .map(|vertex| (vertex, self.source_module.get(vertex)))
+            .collect()
+    }
+}
+
+fn ico_sphere_vertices(subdivisions: usize) -> Vec<[f64; 3]> {
+    // The golden ratio places the icosahedron's 12 vertices on three orthogonal rectangles.
+    let t = (1.0 + 5.0_f64.sqrt()) / 2.0;
+
+    let mut vertices: Vec<[f64; 3]> = [
+        [-1.0, t, 0.0],
+        [1.0, t, 0.0],
+        [-1.0, -t, 0.0],
+        [1.0, -t, 0.0],
+        [0.0, -1.0, t],
+        [0.0, 1.0, t],
+        [0.0, -1.0, -t],
+        [0.0, 1.0, -t],
+        [t, 0.0, -1.0],
+        [t, 0.0, 1.0],
+        [-t, 0.0, -1.0],
+        [-t, 0.0, 1.0],
+    ]
+    .iter()
+    .map(|&vertex| normalize(vertex))
+    .collect();
+
+    let mut faces: Vec<[usize; 3]> = vec![
+        [0, 11, 5],
+        [0, 5, 1],
+        [0, 1, 7],
+        [0, 7, 10],
+        [0, 10, 11],
+        [1, 5, 9],
+        [5, 11, 4],
+        [11, 10, 2],
+        [10, 7, 6],
+        [7, 1, 8],
+        [3, 9, 4],
+        [3, 4, 2],
+        [3, 2, 6],
+        [3, 6, 8],
+        [3, 8, 9],
+        [4, 9, 5],
+        [2, 4, 11],
+        [6, 2, 10],
+        [8, 6, 7],
+        [9, 8, 1],
+    ];
+
+    for _ in 0..subdivisions {
+        // Edges are shared by two faces, so midpoints are cached to avoid duplicate vertices.
+        let mut midpoints = BTreeMap::new();
+        let mut midpoint = |a: usize, b: usize, vertices: &mut Vec<[f64; 3]>| {
+            *midpoints.entry((a.min(b), a.max(b))).or_insert_with(|| {
+                let (va, vb) = (vertices[a], vertices[b]);
+                vertices.push(normalize([va[0] + vb[0], va[1] + vb[1], va[2] + vb[2]]));
+
+                vertices.len() - 1
+            })
+        };
+
+        faces = faces
+            .iter()
+            .flat_map(|&[a, b, c]| {
+                let ab = midpoint(a, b, &mut vertices);
+                let bc = midpoint(b, c, &mut vertices);
+                let ca = midpoint(c, a, &mut vertices);
+
+                [[a, ab, ca], [b, bc, ab], [c, ca, bc], [ab, bc, ca]]
+            })
+            .collect();
+    }
+
+    vertices
+}
+
+fn normalize([x, y, z]: [f64; 3]) -> [f64; 3] {
+    let length = (x * x + y * y + z * z).sqrt();
+
+    [x / length, y / length, z / length]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Perlin;
+
+    #[test]
+    fn vertex_counts_match_subdivision_level() {
+        let builder = IcoSphereBuilder::new(Perlin::new(4)).set_subdivisions(0);
+        let samples = builder.build();
+
+        assert_eq!(12, samples.len());
+        assert!(samples.iter().all(|(_, value)| value.is_finite()));
+
+        let level_two = builder.set_subdivisions(2).build();
+        assert_eq!(162, level_two.len());
+
+        for ([x, y, z], _) in level_two {
+            assert!(((x * x + y * y + z * z) - 1.0).abs() < 1e-12);
+        }
+    }
+}
@@ -0,0 +1,260 @@
+use crate::{utils::NoiseMap, NoiseFn};
+use alloc::{boxed::Box, vec::Vec};
+
+use super::NoiseMapBuilder;
+
+/// A source selected by [`NearestSourceBuilder`], keyed by the control value it represents.
+pub type KeyedSource = (f64, Box<dyn NoiseFn<f64, 3>>);
+
+/// Builds a map on the plane by choosing, for every cell, the source whose key is nearest to the
+/// control module's value at that cell.
+///
+/// This is the usual way of picking a biome per cell: the control module describes, say,
+/// temperature, and each biome's source is keyed by its ideal temperature. Ties go to the source
+/// listed first, and cells hold the [fill value](Self::set_fill_value) when no sources are given.
+/// Cells are laid out as in a non-seamless [`PlaneMapBuilder`](super::PlaneMapBuilder), with
+/// `z = 0`.
+pub struct NearestSourceBuilder<Control>
+where
+    Control: NoiseFn<f64, 3>,
+{
+    x_bounds: (f64, f64),
+    y_bounds: (f64, f64),
+    size: (usize, usize),
+    sources: Vec<KeyedSource>,
+    control: Control,
+    fill_value: f64,
+}
+
+impl<Control> NearestSourceBuilder<Control>
+where
+    Control: NoiseFn<f64, 3>,
+{
+    pub fn new(control: Control, sources: Vec<KeyedSource>) -> Self {
+        NearestSourceBuilder {
+            x_bounds: (-1.0, 1.0),
+            y_bounds: (-1.0, 1.0),
+            size: (100, 100),
+            sources,
+            control,
+            fill_value: 0.0,
+        }
+    }
+
+    pub fn set_x_bounds(self, lower_x_bound: f64, upper_x_bound: f64) -> Self {
+        NearestSourceBuilder {
+            x_bounds: (lower_x_bound, upper_x_bound),
+            ..self
+        }
+    }
+
+    pub fn set_y_bounds(self, lower_y_bound: f64, upper_y_bound: f64) -> Self {
+        NearestSourceBuilder {
+            y_bounds: (lower_y_bound, upper_y_bound),
+            ..self
+        }
+    }
+
+    pub fn set_sources(self, sources: Vec<KeyedSource>) -> Self {
+        NearestSourceBuilder { sources, ..self }
+    }
+
+    /// Sets the value of cells that are not computed during a build, which happens when no
+    /// sources are given. The default is 0.0.
+    pub fn set_fill_value(self, fill_value: f64) -> Self {
+        NearestSourceBuilder { fill_value, ..self }
+    }
+
+    pub fn fill_value(&self) -> f64 {
+        self.fill_value
+    }
+
+    pub fn x_bounds(&self) -> (f64, f64) {
+        self.x_bounds
+    }
+
+    pub fn y_bounds(&self) -> (f64, f64) {
+        self.y_bounds
+    }
+
+    pub fn sources(&self) -> &[KeyedSource] {
+        &self.sources
+    }
+
+    /// Builds the map and returns, for each source in order, the fraction of cells that were
+    /// taken from it.
+    ///
+    /// The fractions sum to 1.0 unless the map is empty, in which case they are all 0.0. The
+    /// list is empty when no sources are given.
+    pub fn build_with_coverage(&self) -> (NoiseMap, Vec<f64>) {
+        let (width, height) = self.size;
+        let mut counts = vec![0_usize; self.sources.len()];
+
+        let map = self.build_cells(|index| counts[index] += 1);
+        let total = (width * height) as f64;
+        let coverage = counts
+            .into_iter()
+            .map(|count| {
+                if total > 0.0 {
+                    count as f64 / total
+                } else {
+                    0.0
+                }
+            })
+            .collect();
+
+        (map, coverage)
+    }
+
+    /// Builds the map, calling `on_select` with the index of the source chosen for each cell.
+    fn build_cells<F>(&self, mut on_select: F) -> NoiseMap
+    where
+        F: FnMut(usize),
+    {
+        let (width, height) = self.size;
+
+        let x_step = (self.x_bounds.1 - self.x_bounds.0) / width as f64;
+        let y_step = (self.y_bounds.1 - self.y_bounds.0) / height as f64;
+
+        let mut map = NoiseMap::new(width, height);
+
+        for y in 0..height {
+            for x in 0..width {
+                let point = [
+                    self.x_bounds.0 + x_step * x as f64,
+                    self.y_bounds.0 + y_step * y as f64,
+                    0.0,
+                ];
+
+                map[(x, y)] = match self.nearest_source(self.control.get(point)) {
+                    Some(index) => {
+                        on_select(index);
+                        self.sources[index].1.get(point)
+                    }
+                    None => self.fill_value,
+                };
+            }
+        }
+
+        map
+    }
+
+    fn nearest_source(&self, control_value: f64) -> Option<usize> {
+        let mut nearest: Option<(f64, usize)> = None;
+
+        for (index, (key, _)) in self.sources.iter().enumerate() {
+            let distance = (key - control_value).abs();
+
+            match nearest {
+                Some((best, _)) if distance >= best => {}
+                _ => nearest = Some((distance, index)),
+            }
+        }
+
+        nearest.map(|(_, index)| index)
+    }
+}
+
+impl<Control> NoiseMapBuilder<Control> for NearestSourceBuilder<Control>
+where
+    Control: NoiseFn<f64, 3>,
+{
+    fn set_size(self, width: usize, height: usize) -> Self {
+        NearestSourceBuilder {
+            size: (width, height),
+            ..self
+        }
+    }
+
+    fn set_source_module(self, control: Control) -> Self {
+        NearestSourceBuilder { control, ..self }
+    }
+
+    fn size(&self) -> (usize, usize) {
+        self.size
+    }
+
+    fn build(&self) -> NoiseMap {
+        self.build_cells(|_| {})
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Constant;
+
+    struct Gradient;
+
+    impl NoiseFn<f64, 3> for Gradient {
+        fn get(&self, point: [f64; 3]) -> f64 {
+            point[0]
+        }
+    }
+
+    #[test]
+    fn nearest_key_selects_source() {
+        let map = NearestSourceBuilder::new(
+            Gradient,
+            vec![
+                (
+                    -0.5,
+                    Box::new(Constant::new(10.0)) as Box<dyn NoiseFn<f64, 3>>,
+                ),
+                (0.5, Box::new(Constant::new(20.0))),
+            ],
+        )
+        .set_size(8, 2)
+        .build();
+
+        // The control value is the x coordinate, running from -1.0 to 0.75. At x = 4 the control
+        // is 0.0, equally far from both keys, so the first source wins.
+        for x in 0..8 {
+            let expected = if x <= 4 { 10.0 } else { 20.0 };
+            assert_eq!(expected, map[(x, 1)]);
+        }
+
+        let empty = NearestSourceBuilder::new(Gradient, Vec::new())
+            .set_size(2, 2)
+            .build();
+        assert!(empty.iter().all(|&value| value == 0.0));
+    }
+
+    #[test]
+    fn fill_value_used_without_sources() {
+        let map = NearestSourceBuilder::new(Gradient, Vec::new())
+            .set_fill_value(-1.0)
+            .set_size(3, 2)
+            .build();
+
+        assert!(map.iter().all(|&value| value == -1.0));
+    }
+
+    #[test]
+    fn coverage_reports_fraction_per_source() {
+        let sources = || {
+            vec![
+                (
+                    -0.5,
+                    Box::new(Constant::new(10.0)) as Box<dyn NoiseFn<f64, 3>>,
+                ),
+                (0.5, Box::new(Constant::new(20.0))),
+            ]
+        };
+
+        // Shifting the bounds by half a cell puts the control value, the x coordinate, at
+        // -0.875..0.875 so neither key wins the tie at 0.0 and the split is even.
+        let builder = NearestSourceBuilder::new(Gradient, sources())
+            .set_x_bounds(-0.875, 1.125)
+            .set_size(8, 3);
+        let (map, coverage) = builder.build_with_coverage();
+
+        assert!(map.iter().eq(builder.build().iter()));
+        assert_eq!(vec![0.5, 0.5], coverage);
+
+        let (_, none) = NearestSourceBuilder::new(Gradient, Vec::new())
+            .set_size(2, 2)
+            .build_with_coverage();
+        assert!(none.is_empty());
+    }
+}
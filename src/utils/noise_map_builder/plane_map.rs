@@ -1,41 +1,222 @@
-use crate::{math::interpolate, utils::NoiseMap, NoiseFn};
+use crate::{
+    math::{interpolate, s_curve::quintic::Quintic},
+    utils::{BoundedNoiseMap, NoiseMap},
+    NoiseFn, NoiseFnDeriv, NoiseFnF32, SeededNoiseFn,
+};
+use alloc::{boxed::Box, sync::Arc, vec::Vec};
+use core::{
+    f64::consts::TAU,
+    ops::Range,
+    sync::atomic::{AtomicBool, AtomicUsize, Ordering},
+};
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+#[cfg(feature = "std")]
+use std::{
+    fs::File,
+    io::{self, Write},
+    path::Path,
+    sync::mpsc::{self, Receiver, Sender},
+    time::{Duration, Instant},
+};
 
-use super::{NoiseFnWrapper, NoiseMapBuilder};
+use super::{NoiseFnWrapper, NoiseMapBuilder, SampleCache};
+
+/// How [`PlaneMapBuilder`] blends each sample with its wrapped counterpart on seamless axes.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SeamlessMode {
+    /// Blend weights vary linearly across the map.
+    #[default]
+    Linear,
+    /// Blend weights follow the quintic S-curve, whose flat ends keep the slope of the map
+    /// continuous across the seam.
+    Smoothstep,
+}
+
+/// How [`PlaneMapBuilder`] dithers values quantized by
+/// [`set_output_bits`](PlaneMapBuilder::set_output_bits).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum DitherMode {
+    /// Values are rounded to the nearest level.
+    #[default]
+    None,
+    /// Values are offset by a 4x4 Bayer matrix before rounding. The pattern depends only on the
+    /// cell's position, so it is deterministic and tiles every four cells.
+    Bayer,
+}
+
+/// The sampling parameters of a [`PlaneMapBuilder`], excluding its source module and any
+/// closures or channels attached to it.
+///
+/// Returned by [`PlaneMapBuilder::config`] and applied with [`PlaneMapBuilder::set_config`],
+/// so a map can be reproduced from a stored configuration and the same source module.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PlaneMapConfig {
+    pub size: (usize, usize),
+    pub x_bounds: (f64, f64),
+    pub y_bounds: (f64, f64),
+    pub seamless_axes: (bool, bool),
+    pub seamless_mode: SeamlessMode,
+    /// Octave count, lacunarity and persistence, as passed to
+    /// [`set_octaves`](PlaneMapBuilder::set_octaves).
+    pub octaves: (usize, f64, f64),
+    pub subpixel_offset: (f64, f64),
+    pub mirror_axes: (bool, bool),
+    pub island_falloff: Option<f64>,
+    pub wrap_period: Option<(f64, f64)>,
+    pub row_stagger: f64,
+    pub fill_value: f64,
+    pub output_bits: Option<u8>,
+    pub dither: DitherMode,
+    pub tileable: bool,
+}
 
 pub struct PlaneMapBuilder<SourceModule, const DIM: usize>
 where
     SourceModule: NoiseFn<f64, DIM>,
 {
-    is_seamless: bool,
+    seamless_axes: (bool, bool),
+    seamless_mode: SeamlessMode,
     x_bounds: (f64, f64),
     y_bounds: (f64, f64),
     size: (usize, usize),
+    compute_mask: Option<ComputeMask>,
+    fill_value: f64,
+    octaves: (usize, f64, f64),
+    subpixel_offset: (f64, f64),
+    mirror_axes: (bool, bool),
+    island_falloff: Option<f64>,
+    wrap_period: Option<(f64, f64)>,
+    row_stagger: f64,
+    output_bits: Option<u8>,
+    dither: DitherMode,
+    tileable: bool,
+    torus_sampler: Option<TorusSampler<SourceModule>>,
+    domain_warp: Option<(DomainWarp, f64)>,
+    fast_sampler: Option<FastSampler<SourceModule, DIM>>,
+    seeded_sampler: Option<(u64, SeededSampler<SourceModule, DIM>)>,
+    #[cfg(feature = "std")]
+    progress: Option<(Sender<(usize, usize)>, usize)>,
+    #[cfg(feature = "std")]
+    cell_timeout: Option<Duration>,
+    #[cfg(feature = "std")]
+    timed_out_cells: AtomicUsize,
+    cache: Option<NoiseMap>,
     source_module: SourceModule,
 }
 
+type ComputeMask = Box<dyn Fn(usize, usize) -> bool + Send + Sync>;
+
+type DomainWarp = Box<dyn NoiseFn<f64, 2> + Send + Sync>;
+
+/// Offset between the points at which the domain warp is sampled for the x and y axes, so the
+/// two displacements are decorrelated.
+const WARP_Y_OFFSET: [f64; 2] = [26519.0 / 65536.0 + 17.0, 18128.0 / 65536.0 + 31.0];
+
+type FastSampler<SourceModule, const DIM: usize> = fn(&SourceModule, [f32; DIM]) -> f32;
+
+type SeededSampler<SourceModule, const DIM: usize> = fn(&SourceModule, [f64; DIM], u64) -> f64;
+
+type TorusSampler<SourceModule> = fn(&SourceModule, [f64; 4]) -> f64;
+
+/// World-space x and y bounds.
+type Bounds = ((f64, f64), (f64, f64));
+
+/// The parameters that can vary between cell samples taken from one builder.
+#[derive(Clone, Copy)]
+struct CellSampling {
+    bounds: Bounds,
+    /// Octave count, lacunarity and persistence.
+    octaves: (usize, f64, f64),
+    /// If set, only this octave's weighted contribution is sampled.
+    layer: Option<usize>,
+    /// If set, no further samples are taken once this instant has passed.
+    #[cfg(feature = "std")]
+    deadline: Option<Instant>,
+}
+
 impl<SourceModule, const DIM: usize> PlaneMapBuilder<SourceModule, DIM>
 where
     SourceModule: NoiseFn<f64, DIM>,
 {
     pub fn new(source_module: SourceModule) -> Self {
         PlaneMapBuilder {
-            is_seamless: false,
+            cache: None,
+            seamless_axes: (false, false),
+            seamless_mode: SeamlessMode::Linear,
             x_bounds: (-1.0, 1.0),
             y_bounds: (-1.0, 1.0),
             size: (100, 100),
+            compute_mask: None,
+            fill_value: 0.0,
+            octaves: (1, 2.0, 0.5),
+            subpixel_offset: (0.0, 0.0),
+            mirror_axes: (false, false),
+            island_falloff: None,
+            wrap_period: None,
+            row_stagger: 0.0,
+            output_bits: None,
+            dither: DitherMode::None,
+            tileable: false,
+            torus_sampler: if DIM >= 4 {
+                Some(sample_torus_in_dim::<SourceModule, DIM>)
+            } else {
+                None
+            },
+            domain_warp: None,
+            fast_sampler: None,
+            seeded_sampler: None,
+            #[cfg(feature = "std")]
+            progress: None,
+            #[cfg(feature = "std")]
+            cell_timeout: None,
+            #[cfg(feature = "std")]
+            timed_out_cells: AtomicUsize::new(0),
             source_module,
         }
     }
 
+    /// Enables seam blending on both axes.
+    ///
+    /// Seamless maps sample both bounds inclusively, spacing cells `extent / (cells - 1)` apart,
+    /// and blend each sample with its wrapped counterpart one extent below it. The first and last
+    /// columns (and rows) of the map are therefore equal, so tiles should overlap by one cell.
+    ///
+    /// Before 0.10 the cells were spaced `extent / cells` apart and blended with the sample one
+    /// extent above them, so seamless maps built with the same settings now differ from earlier
+    /// releases.
     pub fn set_is_seamless(self, is_seamless: bool) -> Self {
+        self.set_seamless_axes(is_seamless, is_seamless)
+    }
+
+    /// Enables seam blending independently on the x and y axes.
+    pub fn set_seamless_axes(self, x: bool, y: bool) -> Self {
+        PlaneMapBuilder {
+            cache: None,
+            seamless_axes: (x, y),
+            ..self
+        }
+    }
+
+    /// Sets how samples are blended on seamless axes. The default is [`SeamlessMode::Linear`].
+    pub fn set_seamless_mode(self, seamless_mode: SeamlessMode) -> Self {
         PlaneMapBuilder {
-            is_seamless,
+            cache: None,
+            seamless_mode,
             ..self
         }
     }
 
+    pub fn seamless_mode(&self) -> SeamlessMode {
+        self.seamless_mode
+    }
+
     pub fn set_x_bounds(self, lower_x_bound: f64, upper_x_bound: f64) -> Self {
         PlaneMapBuilder {
+            cache: None,
             x_bounds: (lower_x_bound, upper_x_bound),
             ..self
         }
@@ -43,263 +224,2490 @@ where
 
     pub fn set_y_bounds(self, lower_y_bound: f64, upper_y_bound: f64) -> Self {
         PlaneMapBuilder {
+            cache: None,
             y_bounds: (lower_y_bound, upper_y_bound),
             ..self
         }
     }
 
-    pub fn x_bounds(&self) -> (f64, f64) {
-        self.x_bounds
+    /// Only samples cells for which `mask(x, y)` returns `true`. All other cells are set to the
+    /// [fill value](Self::set_fill_value) without evaluating the source module.
+    pub fn set_compute_mask<F>(self, mask: F) -> Self
+    where
+        F: Fn(usize, usize) -> bool + Send + Sync + 'static,
+    {
+        PlaneMapBuilder {
+            cache: None,
+            compute_mask: Some(Box::new(mask)),
+            ..self
+        }
     }
 
-    pub fn y_bounds(&self) -> (f64, f64) {
-        self.y_bounds
+    /// Sets the value of cells that are not computed during a build, such as cells excluded by
+    /// the [compute mask](Self::set_compute_mask). Every build method uses this value for such
+    /// cells. The default is 0.0.
+    pub fn set_fill_value(self, fill_value: f64) -> Self {
+        PlaneMapBuilder {
+            cache: None,
+            fill_value,
+            ..self
+        }
     }
-}
 
-impl<SourceModule> NoiseMapBuilder<SourceModule> for PlaneMapBuilder<SourceModule, 3>
-where
-    SourceModule: NoiseFn<f64, 3>,
-{
-    fn set_size(self, width: usize, height: usize) -> Self {
+    pub fn fill_value(&self) -> f64 {
+        self.fill_value
+    }
+
+    /// Returns a receiver that is sent `(completed_rows, total_rows)` every `granularity` rows
+    /// during builds, and once more when the last row completes.
+    ///
+    /// Replaces any previously configured progress channel.
+    #[cfg(feature = "std")]
+    pub fn set_progress_channel(&mut self, granularity: usize) -> Receiver<(usize, usize)> {
+        let (sender, receiver) = mpsc::channel();
+
+        self.progress = Some((sender, granularity.max(1)));
+        self.cache = None;
+
+        receiver
+    }
+
+    /// Substitutes the [fill value](Self::set_fill_value) for any cell whose samples take longer
+    /// than `timeout` to compute, and resets the [timed-out cell count](Self::timed_out_cells).
+    ///
+    /// The elapsed time is checked before every call to the source module, and a cell stops
+    /// sampling as soon as its budget is exceeded, so a slow cell costs at most one call past the
+    /// timeout. A single call cannot be pre-empted, however, so a source that never returns
+    /// still blocks the build.
+    #[cfg(feature = "std")]
+    pub fn set_cell_timeout(self, timeout: Duration) -> Self {
         PlaneMapBuilder {
-            size: (width, height),
+            cache: None,
+            cell_timeout: Some(timeout),
+            timed_out_cells: AtomicUsize::new(0),
             ..self
         }
     }
 
-    fn set_source_module(self, source_module: SourceModule) -> Self {
+    #[cfg(feature = "std")]
+    pub fn cell_timeout(&self) -> Option<Duration> {
+        self.cell_timeout
+    }
+
+    /// Returns the number of cells replaced by the fill value because they exceeded the
+    /// [cell timeout](Self::set_cell_timeout), summed over every build since it was set.
+    #[cfg(feature = "std")]
+    pub fn timed_out_cells(&self) -> usize {
+        self.timed_out_cells.load(Ordering::Relaxed)
+    }
+
+    /// Sums `count` octaves of the source module for every sample.
+    ///
+    /// Octave `i` samples the source at the point scaled by `lacunarity^i` and is weighted by
+    /// `persistence^i`. The sum is divided by the total weight, so a single octave reproduces the
+    /// plain source. The default is a single octave, and a `count` of zero is treated as one.
+    pub fn set_octaves(self, count: usize, lacunarity: f64, persistence: f64) -> Self {
         PlaneMapBuilder {
-            source_module,
+            cache: None,
+            octaves: (count.max(1), lacunarity, persistence),
             ..self
         }
     }
 
-    fn size(&self) -> (usize, usize) {
-        self.size
+    pub fn octaves(&self) -> (usize, f64, f64) {
+        self.octaves
     }
 
-    fn build(&self) -> NoiseMap {
-        let (width, height) = self.size;
+    /// Shifts every sample by `(offset_x, offset_y)` steps, where a step is the distance between
+    /// neighboring cells.
+    ///
+    /// Building several frames with offsets taken from a low-discrepancy sequence (such as
+    /// Halton) and averaging them yields an anti-aliased map. The default is `(0.0, 0.0)`.
+    pub fn set_subpixel_offset(self, offset_x: f64, offset_y: f64) -> Self {
+        PlaneMapBuilder {
+            cache: None,
+            subpixel_offset: (offset_x, offset_y),
+            ..self
+        }
+    }
 
-        let mut result_map = NoiseMap::new(width, height);
+    pub fn subpixel_offset(&self) -> (f64, f64) {
+        self.subpixel_offset
+    }
+
+    /// Makes the map mirror-symmetric about its center on the chosen axes.
+    ///
+    /// Cells in the second half of a mirrored axis reuse the sampling coordinates of their
+    /// counterparts in the first half, so `map[(x, y)] == map[(width - 1 - x, y)]` when mirroring
+    /// on x.
+    pub fn set_mirror(self, x: bool, y: bool) -> Self {
+        PlaneMapBuilder {
+            cache: None,
+            mirror_axes: (x, y),
+            ..self
+        }
+    }
 
-        let x_extent = self.x_bounds.1 - self.x_bounds.0;
-        let y_extent = self.y_bounds.1 - self.y_bounds.0;
+    pub fn mirror_axes(&self) -> (bool, bool) {
+        self.mirror_axes
+    }
 
-        let x_step = x_extent / width as f64;
-        let y_step = y_extent / height as f64;
+    /// Multiplies every sample by the radial falloff `max(1 - (distance / max_distance)^power,
+    /// 0)`, where `distance` is measured in cells from the center of the map and `max_distance`
+    /// is the distance to a corner.
+    ///
+    /// The center of the map keeps its value while the corners fade to zero, which shapes the map
+    /// into an island. [`build_with_derivatives`](Self::build_with_derivatives) includes the
+    /// falloff in its derivatives.
+    pub fn set_island_falloff(self, power: f64) -> Self {
+        PlaneMapBuilder {
+            cache: None,
+            island_falloff: Some(power),
+            ..self
+        }
+    }
 
-        for y in 0..height {
-            let current_y = self.y_bounds.0 + y_step * y as f64;
+    pub fn island_falloff(&self) -> Option<f64> {
+        self.island_falloff
+    }
 
-            for x in 0..width {
-                let current_x = self.x_bounds.0 + x_step * x as f64;
-
-                let final_value = if self.is_seamless {
-                    let sw_value = self.source_module.get([current_x, current_y, 0.0]);
-                    let se_value = self
-                        .source_module
-                        .get([current_x + x_extent, current_y, 0.0]);
-                    let nw_value = self
-                        .source_module
-                        .get([current_x, current_y + y_extent, 0.0]);
-                    let ne_value =
-                        self.source_module
-                            .get([current_x + x_extent, current_y + y_extent, 0.0]);
-
-                    let x_blend = 1.0 - ((current_x - self.x_bounds.0) / x_extent);
-                    let y_blend = 1.0 - ((current_y - self.y_bounds.0) / y_extent);
-
-                    let y0 = interpolate::linear(sw_value, se_value, x_blend);
-                    let y1 = interpolate::linear(nw_value, ne_value, x_blend);
-
-                    interpolate::linear(y0, y1, y_blend)
-                } else {
-                    self.source_module.get([current_x, current_y, 0.0])
-                };
+    /// Wraps the x and y coordinates of every source point into `[0, period_x)` and
+    /// `[0, period_y)` before sampling.
+    ///
+    /// For sources that repeat with a known period, this makes maps whose extent is a multiple of
+    /// the period tile exactly, without the blending of [`set_is_seamless`](Self::set_is_seamless).
+    pub fn set_wrap_period(self, period_x: f64, period_y: f64) -> Self {
+        PlaneMapBuilder {
+            cache: None,
+            wrap_period: Some((period_x, period_y)),
+            ..self
+        }
+    }
 
-                result_map[(x, y)] = final_value;
-            }
+    pub fn wrap_period(&self) -> Option<(f64, f64)> {
+        self.wrap_period
+    }
+
+    /// Makes the map tile exactly on both axes by sampling the source on a torus in 4D space.
+    ///
+    /// Each axis is wrapped onto a circle whose circumference is the axis' extent, so a point
+    /// `(x, y)` is sampled at `(r_x cos u, r_x sin u, r_y cos v, r_y sin v)` where `u` and `v` are
+    /// the point's angles around the circles. Shifting the bounds by a whole extent leaves the
+    /// map unchanged, with no blending involved. Octaves scale the 4D point, and the
+    /// [wrap period](Self::set_wrap_period) is ignored while this is enabled. This is intended
+    /// for maps that are not [seamless](Self::set_is_seamless), whose cells already span
+    /// exactly one period.
+    ///
+    /// The source only needs to implement `NoiseFn<f64, 4>` in addition to the builder's own
+    /// dimension, so the crate's generators can be used directly with the usual 3D builder. The
+    /// 4D point is passed straight to the source, so [fast preview](Self::set_fast_preview) and
+    /// [cell seeds](Self::set_cell_seed) are not applied to it. The
+    /// [domain warp](Self::set_domain_warp) offsets are not periodic, so a warped map does not
+    /// tile.
+    pub fn set_tileable(self, tileable: bool) -> Self
+    where
+        SourceModule: NoiseFn<f64, 4>,
+    {
+        PlaneMapBuilder {
+            cache: None,
+            tileable,
+            torus_sampler: Some(<SourceModule as NoiseFn<f64, 4>>::get),
+            ..self
         }
+    }
 
-        result_map
+    pub fn tileable(&self) -> bool {
+        self.tileable
     }
-}
 
-impl<SourceFn, const DIM: usize> PlaneMapBuilder<NoiseFnWrapper<SourceFn, DIM>, DIM>
-where
-    SourceFn: Fn([f64; DIM]) -> f64,
-{
-    pub fn new_fn(source_fn: SourceFn) -> Self {
+    /// Displaces every sample point by `strength` times the value of `warp_source` before the
+    /// source module is sampled.
+    ///
+    /// The x offset is `warp_source` at the point itself and the y offset is `warp_source` at a
+    /// fixed offset from it, so the two axes are displaced independently. The warp is applied
+    /// before the octave frequency scaling. It is not applied by
+    /// [`build_with_derivatives`](Self::build_with_derivatives) or
+    /// [`build_with_tbn`](Self::build_with_tbn). Since the offsets are not periodic, warping
+    /// breaks the tiling of [tileable](Self::set_tileable) maps.
+    pub fn set_domain_warp<W>(self, warp_source: W, strength: f64) -> Self
+    where
+        W: NoiseFn<f64, 2> + Send + Sync + 'static,
+    {
         PlaneMapBuilder {
-            is_seamless: false,
-            x_bounds: (-1.0, 1.0),
-            y_bounds: (-1.0, 1.0),
-            size: (100, 100),
-            source_module: NoiseFnWrapper { source_fn },
+            cache: None,
+            domain_warp: Some((Box::new(warp_source), strength)),
+            ..self
         }
     }
 
-    pub fn set_size(self, width: usize, height: usize) -> Self {
+    /// Quantizes every sampled value to `bits` bits, giving `2^bits` evenly spaced levels across
+    /// `[-1, 1]`. Values outside that range are clamped first, and `bits` is clamped to
+    /// `1..=32`.
+    ///
+    /// Cells not sampled, such as those excluded by the
+    /// [compute mask](Self::set_compute_mask), keep the fill value. Combine this with
+    /// [`set_dither`](Self::set_dither) to reduce banding.
+    pub fn set_output_bits(self, bits: u8) -> Self {
         PlaneMapBuilder {
-            size: (width, height),
+            cache: None,
+            output_bits: Some(bits.clamp(1, 32)),
             ..self
         }
     }
-}
 
-impl<SourceFn> PlaneMapBuilder<NoiseFnWrapper<SourceFn, 2>, 2>
-where
-    SourceFn: Fn([f64; 2]) -> f64,
-{
-    pub fn build(&self) -> NoiseMap {
-        let (width, height) = self.size;
+    pub fn output_bits(&self) -> Option<u8> {
+        self.output_bits
+    }
 
-        let mut result_map = NoiseMap::new(width, height);
+    /// Sets how values quantized by [`set_output_bits`](Self::set_output_bits) are dithered.
+    /// The default is [`DitherMode::None`].
+    pub fn set_dither(self, dither: DitherMode) -> Self {
+        PlaneMapBuilder {
+            cache: None,
+            dither,
+            ..self
+        }
+    }
 
-        let x_extent = self.x_bounds.1 - self.x_bounds.0;
-        let y_extent = self.y_bounds.1 - self.y_bounds.0;
+    pub fn dither(&self) -> DitherMode {
+        self.dither
+    }
 
-        let x_step = x_extent / width as f64;
-        let y_step = y_extent / height as f64;
+    /// Shifts the samples of every odd row along x by `offset` steps, giving a brick-like,
+    /// staggered grid. The default is 0.0.
+    pub fn set_row_stagger(self, offset: f64) -> Self {
+        PlaneMapBuilder {
+            cache: None,
+            row_stagger: offset,
+            ..self
+        }
+    }
 
-        for y in 0..height {
-            let current_y = self.y_bounds.0 + y_step * y as f64;
+    pub fn row_stagger(&self) -> f64 {
+        self.row_stagger
+    }
+
+    /// Samples the source module through [`NoiseFnF32::get_f32`] with single-precision points
+    /// when enabled, trading accuracy for speed in previews.
+    ///
+    /// [`build_with_derivatives`](Self::build_with_derivatives) always samples in double
+    /// precision.
+    pub fn set_fast_preview(self, fast_preview: bool) -> Self
+    where
+        SourceModule: NoiseFnF32<DIM>,
+    {
+        let fast_sampler: Option<FastSampler<SourceModule, DIM>> = if fast_preview {
+            Some(<SourceModule as NoiseFnF32<DIM>>::get_f32)
+        } else {
+            None
+        };
+
+        PlaneMapBuilder {
+            cache: None,
+            fast_sampler,
+            ..self
+        }
+    }
+
+    pub fn fast_preview(&self) -> bool {
+        self.fast_sampler.is_some()
+    }
+
+    /// Samples the source module through [`SeededNoiseFn::get_seeded`], passing a seed derived
+    /// deterministically from each cell's coordinates and `base_seed`.
+    ///
+    /// Every sample of a cell, including its seamless counterparts and all octaves, receives the
+    /// same seed, and the same cell always receives the same seed for a given `base_seed`. Takes
+    /// precedence over [fast preview](Self::set_fast_preview).
+    /// [`build_with_derivatives`](Self::build_with_derivatives) does not pass seeds.
+    pub fn set_cell_seed(self, base_seed: u64) -> Self
+    where
+        SourceModule: SeededNoiseFn<DIM>,
+    {
+        let sampler: SeededSampler<SourceModule, DIM> =
+            <SourceModule as SeededNoiseFn<DIM>>::get_seeded;
+
+        PlaneMapBuilder {
+            cache: None,
+            seeded_sampler: Some((base_seed, sampler)),
+            ..self
+        }
+    }
+
+    pub fn cell_seed(&self) -> Option<u64> {
+        self.seeded_sampler.map(|(base_seed, _)| base_seed)
+    }
+
+    /// Equivalent to [`set_x_bounds`](Self::set_x_bounds)`(range.start, range.end)`.
+    pub fn set_x_range(self, range: Range<f64>) -> Self {
+        self.set_x_bounds(range.start, range.end)
+    }
+
+    /// Equivalent to [`set_y_bounds`](Self::set_y_bounds)`(range.start, range.end)`.
+    pub fn set_y_range(self, range: Range<f64>) -> Self {
+        self.set_y_bounds(range.start, range.end)
+    }
+
+    pub fn x_bounds(&self) -> (f64, f64) {
+        self.x_bounds
+    }
+
+    pub fn y_bounds(&self) -> (f64, f64) {
+        self.y_bounds
+    }
+
+    pub fn seamless_axes(&self) -> (bool, bool) {
+        self.seamless_axes
+    }
+
+    /// Returns two maps holding the x and y world coordinates sampled for each cell.
+    ///
+    /// For seamless builds these are the coordinates of the unwrapped sample.
+    pub fn build_coords(&self) -> (NoiseMap, NoiseMap) {
+        let (width, height) = self.size;
+
+        let mut x_map = NoiseMap::new(width, height);
+        let mut y_map = NoiseMap::new(width, height);
 
+        for y in 0..height {
             for x in 0..width {
-                let current_x = self.x_bounds.0 + x_step * x as f64;
+                let (current_x, current_y) = self.cell_coords(x as isize, y as isize);
 
-                let final_value = if self.is_seamless {
-                    let sw_value = self.source_module.get([current_x, current_y]);
-                    let se_value = self.source_module.get([current_x + x_extent, current_y]);
-                    let nw_value = self.source_module.get([current_x, current_y + y_extent]);
-                    let ne_value = self
-                        .source_module
-                        .get([current_x + x_extent, current_y + y_extent]);
+                x_map[(x, y)] = current_x;
+                y_map[(x, y)] = current_y;
+            }
+        }
 
-                    let x_blend = 1.0 - ((current_x - self.x_bounds.0) / x_extent);
-                    let y_blend = 1.0 - ((current_y - self.y_bounds.0) / y_extent);
+        (x_map, y_map)
+    }
 
-                    let y0 = interpolate::linear(sw_value, se_value, x_blend);
-                    let y1 = interpolate::linear(nw_value, ne_value, x_blend);
+    /// Returns the world coordinates of a cell. Cells outside the map continue the sampling
+    /// grid.
+    fn cell_coords(&self, x: isize, y: isize) -> (f64, f64) {
+        self.cell_coords_within(self.bounds(), x, y)
+    }
 
-                    interpolate::linear(y0, y1, y_blend)
-                } else {
-                    self.source_module.get([current_x, current_y])
-                };
+    /// Wraps cells outside the map back into it along seamless axes, whose first and last cells
+    /// coincide, so that the seam blend is never evaluated outside the bounds.
+    fn wrap_seamless_cell(&self, x: isize, y: isize) -> (isize, isize) {
+        let (width, height) = self.size;
+        let (seamless_x, seamless_y) = self.seamless_axes;
 
-                result_map[(x, y)] = final_value;
+        let wrap = |cell: isize, size: usize, seamless: bool| {
+            if seamless && size > 1 && !(0..size as isize).contains(&cell) {
+                cell.rem_euclid(size as isize - 1)
+            } else {
+                cell
             }
+        };
+
+        (wrap(x, width, seamless_x), wrap(y, height, seamless_y))
+    }
+
+    fn cell_coords_within(&self, bounds: Bounds, x: isize, y: isize) -> (f64, f64) {
+        let (x_bounds, y_bounds) = bounds;
+        let (width, height) = self.size;
+        let (seamless_x, seamless_y) = self.seamless_axes;
+        let (mirror_x, mirror_y) = self.mirror_axes;
+
+        let x = if mirror_x {
+            x.min(width as isize - 1 - x)
+        } else {
+            x
+        };
+        let y = if mirror_y {
+            y.min(height as isize - 1 - y)
+        } else {
+            y
+        };
+
+        let x_step = axis_step(x_bounds, width, seamless_x);
+        let y_step = axis_step(y_bounds, height, seamless_y);
+        let (offset_x, offset_y) = self.subpixel_offset;
+        let stagger = if y.rem_euclid(2) == 1 {
+            self.row_stagger
+        } else {
+            0.0
+        };
+
+        (
+            x_bounds.0 + x_step * (x as f64 + offset_x + stagger),
+            y_bounds.0 + y_step * (y as f64 + offset_y),
+        )
+    }
+
+    /// Returns the builder's sampling parameters.
+    pub fn config(&self) -> PlaneMapConfig {
+        PlaneMapConfig {
+            size: self.size,
+            x_bounds: self.x_bounds,
+            y_bounds: self.y_bounds,
+            seamless_axes: self.seamless_axes,
+            seamless_mode: self.seamless_mode,
+            octaves: self.octaves,
+            subpixel_offset: self.subpixel_offset,
+            mirror_axes: self.mirror_axes,
+            island_falloff: self.island_falloff,
+            wrap_period: self.wrap_period,
+            row_stagger: self.row_stagger,
+            fill_value: self.fill_value,
+            output_bits: self.output_bits,
+            dither: self.dither,
+            tileable: self.tileable,
         }
+    }
 
-        result_map
+    /// Replaces the builder's sampling parameters with those of `config`, leaving its source
+    /// module and any attached closures or channels untouched.
+    ///
+    /// The source must implement `NoiseFn<f64, 4>` so that [tileable](Self::set_tileable)
+    /// configurations can be reproduced.
+    pub fn set_config(self, config: PlaneMapConfig) -> Self
+    where
+        SourceModule: NoiseFn<f64, 4>,
+    {
+        let (count, lacunarity, persistence) = config.octaves;
+
+        PlaneMapBuilder {
+            cache: None,
+            size: config.size,
+            x_bounds: config.x_bounds,
+            y_bounds: config.y_bounds,
+            seamless_axes: config.seamless_axes,
+            seamless_mode: config.seamless_mode,
+            octaves: (count.max(1), lacunarity, persistence),
+            subpixel_offset: config.subpixel_offset,
+            mirror_axes: config.mirror_axes,
+            island_falloff: config.island_falloff,
+            wrap_period: config.wrap_period,
+            row_stagger: config.row_stagger,
+            fill_value: config.fill_value,
+            output_bits: config.output_bits.map(|bits| bits.clamp(1, 32)),
+            dither: config.dither,
+            tileable: config.tileable,
+            torus_sampler: Some(<SourceModule as NoiseFn<f64, 4>>::get),
+            ..self
+        }
     }
-}
 
-impl<SourceFn> PlaneMapBuilder<NoiseFnWrapper<SourceFn, 3>, 3>
-where
-    SourceFn: Fn([f64; 3]) -> f64,
-{
-    pub fn build(&self) -> NoiseMap {
+    /// Builds the map and writes it to `image_path` as a PPM image (see
+    /// [`NoiseMap::write_to_ppm`]), then writes the builder's [`config`](Self::config) to
+    /// `meta_path` as JSON.
+    #[cfg(feature = "serde")]
+    pub fn write_with_metadata(&self, image_path: &Path, meta_path: &Path) -> io::Result<()> {
+        self.build_map().write_to_ppm(image_path)?;
+
+        let mut file = io::BufWriter::new(File::create(meta_path)?);
+        serde_json::to_writer_pretty(&mut file, &self.config()).map_err(io::Error::other)?;
+
+        file.flush()
+    }
+
+    /// Builds the map, or returns a copy of the previous result if no setter has been called
+    /// since the last call to `build_cached`.
+    ///
+    /// Builds made through other methods neither use nor update the cache.
+    pub fn build_cached(&mut self) -> NoiseMap {
+        if let Some(map) = &self.cache {
+            return map.clone();
+        }
+
+        let map = self.build_map();
+        self.cache = Some(map.clone());
+
+        map
+    }
+
+    /// Builds one tile of the map together with a margin of `margin` cells on every side.
+    ///
+    /// The map is divided into square tiles of `tile_size` cells, and the result covers tile
+    /// `(tile_x, tile_y)` plus its margin, for a size of `tile_size + 2 * margin` on each axis.
+    /// The margin continues the sampling grid past the tile, and past the map's bounds where
+    /// necessary, so filters applied to the result see correct neighbors before it is cropped
+    /// back to the tile. On seamless axes, margin cells past the map's edges wrap around to the
+    /// opposite side instead.
+    pub fn build_tile_with_margin(
+        &self,
+        tile_x: usize,
+        tile_y: usize,
+        tile_size: usize,
+        margin: usize,
+    ) -> NoiseMap {
+        let size = tile_size + 2 * margin;
+        let origin_x = (tile_x * tile_size) as isize - margin as isize;
+        let origin_y = (tile_y * tile_size) as isize - margin as isize;
+
+        NoiseMap::from_fn(size, size, |x, y| {
+            let (x, y) = self.wrap_seamless_cell(origin_x + x as isize, origin_y + y as isize);
+            self.fill_cell(x, y)
+        })
+    }
+
+    /// Builds the map expanded by `context` cells on every side, returning it together with
+    /// `context`.
+    ///
+    /// As with [`build_tile_with_margin`](Self::build_tile_with_margin), the extra cells continue
+    /// the sampling grid past the map's bounds, so simulations such as erosion can run on the
+    /// expanded map and have the cells at its original edges see real neighbors. Cropping
+    /// `context` cells from every side gives back the result of a plain build. On seamless axes,
+    /// context cells wrap around to the opposite side of the map.
+    pub fn build_with_context(&self, context: usize) -> (NoiseMap, usize) {
         let (width, height) = self.size;
+        let offset = context as isize;
 
-        let mut result_map = NoiseMap::new(width, height);
+        let map = NoiseMap::from_fn(width + 2 * context, height + 2 * context, |x, y| {
+            let (x, y) = self.wrap_seamless_cell(x as isize - offset, y as isize - offset);
+            self.fill_cell(x, y)
+        });
 
-        let x_extent = self.x_bounds.1 - self.x_bounds.0;
-        let y_extent = self.y_bounds.1 - self.y_bounds.0;
+        (map, context)
+    }
 
-        let x_step = x_extent / width as f64;
-        let y_step = y_extent / height as f64;
+    /// Builds one map per octave of an fBm with `octaves` octaves, holding that octave's
+    /// weighted contribution, so that summing the layers cell by cell reproduces a build with
+    /// [`set_octaves`](Self::set_octaves)`(octaves, lacunarity, persistence)`.
+    ///
+    /// Layer `i` samples the source at frequency `lacunarity^i` and is weighted by
+    /// `persistence^i` divided by the sum of all weights. Every other setting applies as in a
+    /// normal build, except the [output bit depth](Self::set_output_bits), which would not sum
+    /// back to the full map. Cells excluded by the [compute mask](Self::set_compute_mask) hold
+    /// the fill value in the first layer and 0.0 in the others.
+    pub fn build_octave_layers(
+        &self,
+        octaves: usize,
+        lacunarity: f64,
+        persistence: f64,
+    ) -> Vec<NoiseMap> {
+        let (width, height) = self.size;
+        let octaves = octaves.max(1);
 
-        for y in 0..height {
-            let current_y = self.y_bounds.0 + y_step * y as f64;
+        (0..octaves)
+            .map(|layer| {
+                let sampling = CellSampling {
+                    octaves: (octaves, lacunarity, persistence),
+                    layer: Some(layer),
+                    ..self.sampling()
+                };
 
-            for x in 0..width {
-                let current_x = self.x_bounds.0 + x_step * x as f64;
-
-                let final_value = if self.is_seamless {
-                    let sw_value = self.source_module.get([current_x, current_y, 0.0]);
-                    let se_value = self
-                        .source_module
-                        .get([current_x + x_extent, current_y, 0.0]);
-                    let nw_value = self
-                        .source_module
-                        .get([current_x, current_y + y_extent, 0.0]);
-                    let ne_value =
-                        self.source_module
-                            .get([current_x + x_extent, current_y + y_extent, 0.0]);
-
-                    let x_blend = 1.0 - ((current_x - self.x_bounds.0) / x_extent);
-                    let y_blend = 1.0 - ((current_y - self.y_bounds.0) / y_extent);
-
-                    let y0 = interpolate::linear(sw_value, se_value, x_blend);
-                    let y1 = interpolate::linear(nw_value, ne_value, x_blend);
-
-                    interpolate::linear(y0, y1, y_blend)
+                NoiseMap::from_fn(width, height, |x, y| {
+                    self.fill_cell_within(sampling, x as isize, y as isize)
+                })
+            })
+            .collect()
+    }
+
+    /// Builds `frames` maps whose bounds move linearly from the current bounds to `end_x` and
+    /// `end_y`.
+    ///
+    /// The first frame equals [`build`](NoiseMapBuilder::build) and the last uses the end bounds
+    /// exactly. A single frame uses the current bounds, and zero frames return an empty `Vec`.
+    pub fn build_animated_bounds(
+        &self,
+        end_x: (f64, f64),
+        end_y: (f64, f64),
+        frames: usize,
+    ) -> Vec<NoiseMap> {
+        let (width, height) = self.size;
+        let lerp = |start: (f64, f64), end: (f64, f64), alpha: f64| {
+            (
+                interpolate::linear(start.0, end.0, alpha),
+                interpolate::linear(start.1, end.1, alpha),
+            )
+        };
+
+        (0..frames)
+            .map(|frame| {
+                let alpha = if frames > 1 {
+                    frame as f64 / (frames - 1) as f64
                 } else {
-                    self.source_module.get([current_x, current_y, 0.0])
+                    0.0
+                };
+                let bounds = (
+                    lerp(self.x_bounds, end_x, alpha),
+                    lerp(self.y_bounds, end_y, alpha),
+                );
+
+                let sampling = CellSampling {
+                    bounds,
+                    ..self.sampling()
                 };
 
-                result_map[(x, y)] = final_value;
+                NoiseMap::from_fn(width, height, |x, y| {
+                    self.fill_cell_within(sampling, x as isize, y as isize)
+                })
+            })
+            .collect()
+    }
+
+    /// Builds the map together with a thumbnail whose larger side is at most `thumb_size` cells,
+    /// accumulated while the map is filled.
+    ///
+    /// Each thumbnail cell is the mean of a square block of `factor` cells, where `factor` is
+    /// the map's larger side divided by `thumb_size` and rounded up. Blocks along the right and
+    /// bottom edges are cropped to the map and average only the cells they cover.
+    pub fn build_with_thumbnail(&self, thumb_size: usize) -> (NoiseMap, NoiseMap) {
+        let (width, height) = self.size;
+        let factor = width.max(height).div_ceil(thumb_size.max(1)).max(1);
+        let (thumb_width, thumb_height) = (width.div_ceil(factor), height.div_ceil(factor));
+
+        let mut result_map = NoiseMap::new(width, height);
+        let mut sums = vec![0.0; thumb_width * thumb_height];
+        let completed_rows = AtomicUsize::new(0);
+
+        for (y, row) in result_map
+            .as_mut_slice()
+            .chunks_mut(width.max(1))
+            .enumerate()
+        {
+            self.fill_row(y, row);
+
+            let thumb_row = &mut sums[(y / factor) * thumb_width..][..thumb_width];
+            for (x, value) in row.iter().enumerate() {
+                thumb_row[x / factor] += value;
             }
+
+            self.finish_row(&completed_rows);
         }
 
-        result_map
+        let thumbnail = NoiseMap::from_fn(thumb_width, thumb_height, |x, y| {
+            let block_width = factor.min(width - x * factor);
+            let block_height = factor.min(height - y * factor);
+
+            sums[x + y * thumb_width] / (block_width * block_height) as f64
+        });
+
+        (result_map, thumbnail)
     }
-}
 
-impl<SourceFn> PlaneMapBuilder<NoiseFnWrapper<SourceFn, 4>, 4>
-where
-    SourceFn: Fn([f64; 4]) -> f64,
-{
-    pub fn build(&self) -> NoiseMap {
+    /// Builds only rows `y_start..y_end` of the map, sampled at the same world coordinates as
+    /// they would be in a full build.
+    ///
+    /// The result is `width` by `y_end - y_start` cells, so builds of consecutive row ranges
+    /// can be joined with [`NoiseMap::concat_v`] to reconstruct the full map.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `y_start > y_end` or `y_end` is greater than the map's height.
+    pub fn build_rows(&self, y_start: usize, y_end: usize) -> NoiseMap {
         let (width, height) = self.size;
+        assert!(
+            y_start <= y_end && y_end <= height,
+            "row range must lie within the map"
+        );
 
-        let mut result_map = NoiseMap::new(width, height);
+        let mut result_map = NoiseMap::new(width, y_end - y_start);
 
-        let x_extent = self.x_bounds.1 - self.x_bounds.0;
-        let y_extent = self.y_bounds.1 - self.y_bounds.0;
+        for (y, row) in result_map
+            .as_mut_slice()
+            .chunks_mut(width.max(1))
+            .enumerate()
+        {
+            self.fill_row(y_start + y, row);
+        }
 
-        let x_step = x_extent / width as f64;
-        let y_step = y_extent / height as f64;
+        result_map
+    }
 
-        for y in 0..height {
-            let current_y = self.y_bounds.0 + y_step * y as f64;
+    /// Builds the map one tile at a time, writing each tile to `dir` as soon as it is sampled so
+    /// that the whole map is never held in memory.
+    ///
+    /// Tiles are `tile_size` cells square, except along the right and bottom edges where they
+    /// are cropped to the map. Tile `(x, y)` is written to `tile_{x}_{y}.raw` as its values in
+    /// row-major order, each a little-endian `f64`. The directory must already exist.
+    #[cfg(feature = "std")]
+    pub fn build_tiles_to_dir(&self, dir: &Path, tile_size: usize) -> io::Result<()> {
+        if tile_size == 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "tile size must be positive",
+            ));
+        }
 
-            for x in 0..width {
-                let current_x = self.x_bounds.0 + x_step * x as f64;
-
-                let final_value = if self.is_seamless {
-                    let sw_value = self.source_module.get([current_x, current_y, 0.0, 0.5]);
-                    let se_value =
-                        self.source_module
-                            .get([current_x + x_extent, current_y, 0.0, 0.5]);
-                    let nw_value =
-                        self.source_module
-                            .get([current_x, current_y + y_extent, 0.0, 0.5]);
-                    let ne_value = self.source_module.get([
-                        current_x + x_extent,
-                        current_y + y_extent,
-                        0.0,
-                        0.5,
-                    ]);
-
-                    let x_blend = 1.0 - ((current_x - self.x_bounds.0) / x_extent);
-                    let y_blend = 1.0 - ((current_y - self.y_bounds.0) / y_extent);
-
-                    let y0 = interpolate::linear(sw_value, se_value, x_blend);
-                    let y1 = interpolate::linear(nw_value, ne_value, x_blend);
-
-                    interpolate::linear(y0, y1, y_blend)
-                } else {
-                    self.source_module.get([current_x, current_y, 0.0, 0.5])
-                };
+        let (width, height) = self.size;
+
+        for tile_y in 0..height.div_ceil(tile_size) {
+            for tile_x in 0..width.div_ceil(tile_size) {
+                let (origin_x, origin_y) = (tile_x * tile_size, tile_y * tile_size);
+                let tile_width = tile_size.min(width - origin_x);
+                let tile_height = tile_size.min(height - origin_y);
+
+                let path = dir.join(format!("tile_{}_{}.raw", tile_x, tile_y));
+                let mut file = io::BufWriter::new(File::create(path)?);
 
-                result_map[(x, y)] = final_value;
+                for y in origin_y..origin_y + tile_height {
+                    for x in origin_x..origin_x + tile_width {
+                        let value = self.fill_cell(x as isize, y as isize);
+                        file.write_all(&value.to_le_bytes())?;
+                    }
+                }
+
+                file.flush()?;
             }
         }
 
-        result_map
+        Ok(())
+    }
+
+    /// Builds the map and pairs it with the world-space bounds its cells cover.
+    ///
+    /// For seamless axes the upper bound is extended by one cell, since those axes sample both
+    /// of the configured bounds.
+    pub fn build_bounded(&self) -> BoundedNoiseMap {
+        let (x_bounds, y_bounds) = self.world_bounds();
+
+        BoundedNoiseMap::new(self.build_map(), x_bounds, y_bounds)
+    }
+
+    /// Returns the world-space distance between neighboring cells on the x and y axes.
+    ///
+    /// This is `extent / size` on each axis, or `extent / (size - 1)` on seamless axes.
+    pub fn cell_size(&self) -> (f64, f64) {
+        let (width, height) = self.size;
+        let (seamless_x, seamless_y) = self.seamless_axes;
+
+        (
+            axis_step(self.x_bounds, width, seamless_x),
+            axis_step(self.y_bounds, height, seamless_y),
+        )
+    }
+
+    /// Returns the world-space x and y bounds covered by the map's cells, matching those of
+    /// [`build_bounded`](Self::build_bounded).
+    pub fn world_bounds(&self) -> ((f64, f64), (f64, f64)) {
+        let (width, height) = self.size;
+        let (x_step, y_step) = self.cell_size();
+
+        (
+            (self.x_bounds.0, self.x_bounds.0 + x_step * width as f64),
+            (self.y_bounds.0, self.y_bounds.0 + y_step * height as f64),
+        )
+    }
+
+    /// Builds the map, checking `cancel` before each row and returning `None` once it is set.
+    pub fn build_cancellable(&self, cancel: Arc<AtomicBool>) -> Option<NoiseMap> {
+        self.build_checking_cancel(Some(&cancel))
+    }
+
+    /// Parallel version of [`build_cancellable`](Self::build_cancellable). Rows that have not
+    /// started when `cancel` is set are skipped.
+    #[cfg(feature = "rayon")]
+    pub fn par_build_cancellable(&self, cancel: Arc<AtomicBool>) -> Option<NoiseMap>
+    where
+        SourceModule: Sync,
+    {
+        let (width, height) = self.size;
+
+        let mut result_map = NoiseMap::new(width, height);
+
+        let completed_rows = AtomicUsize::new(0);
+
+        result_map
+            .as_mut_slice()
+            .par_chunks_mut(width.max(1))
+            .enumerate()
+            .try_for_each(|(y, row)| {
+                if cancel.load(Ordering::Relaxed) {
+                    None
+                } else {
+                    self.fill_row(y, row);
+                    self.finish_row(&completed_rows);
+                    Some(())
+                }
+            })
+            .map(|()| result_map)
+    }
+
+    /// Builds the value map together with maps of its partial derivatives along the plane's x
+    /// and y axes, as reported by [`NoiseFnDeriv::get_with_derivative`].
+    ///
+    /// Seamless blending is not applied; every cell is sampled at its unblended coordinates.
+    /// Cells excluded by the compute mask get the fill value and zero derivatives.
+    pub fn build_with_derivatives(&self) -> (NoiseMap, NoiseMap, NoiseMap)
+    where
+        SourceModule: NoiseFnDeriv<DIM>,
+    {
+        let (width, height) = self.size;
+
+        let mut value_map = NoiseMap::new(width, height);
+        let mut dx_map = NoiseMap::new(width, height);
+        let mut dy_map = NoiseMap::new(width, height);
+
+        for y in 0..height {
+            for x in 0..width {
+                if matches!(&self.compute_mask, Some(mask) if !mask(x, y)) {
+                    value_map[(x, y)] = self.fill_value;
+                    continue;
+                }
+
+                let (current_x, current_y) = self.cell_coords(x as isize, y as isize);
+
+                let (count, lacunarity, persistence) = self.octaves;
+
+                let mut frequency = 1.0;
+                let mut amplitude = 1.0;
+                let mut max_amplitude = 0.0;
+                let (mut value, mut dx, mut dy) = (0.0, 0.0, 0.0);
+
+                for _ in 0..count {
+                    let (octave_value, derivative) = self.source_module.get_with_derivative(
+                        self.source_point(current_x * frequency, current_y * frequency),
+                    );
+
+                    value += amplitude * octave_value;
+                    dx += amplitude * frequency * derivative[0];
+                    dy += amplitude * frequency * derivative[1];
+                    max_amplitude += amplitude;
+
+                    frequency *= lacunarity;
+                    amplitude *= persistence;
+                }
+
+                let (value, dx, dy) = (
+                    value / max_amplitude,
+                    dx / max_amplitude,
+                    dy / max_amplitude,
+                );
+                let (falloff, [falloff_dx, falloff_dy]) =
+                    self.island_factor(x as isize, y as isize);
+
+                value_map[(x, y)] = value * falloff;
+                dx_map[(x, y)] = dx * falloff + value * falloff_dx;
+                dy_map[(x, y)] = dy * falloff + value * falloff_dy;
+            }
+        }
+
+        (value_map, dx_map, dy_map)
+    }
+
+    /// Builds the height map together with a per-cell tangent frame for normal mapping.
+    ///
+    /// Returns the map and, in row-major order, the normal, tangent and bitangent of every cell.
+    /// The surface is y-up, with map x along the world x axis and map y along the world z axis,
+    /// and heights scaled by `height_scale`. Slopes are estimated with central differences in
+    /// world units, falling back to one-sided differences at the edges. Each frame is
+    /// orthonormal: the tangent follows +x and the bitangent +z as closely as the normal
+    /// allows.
+    #[allow(clippy::type_complexity)]
+    pub fn build_with_tbn(
+        &self,
+        height_scale: f64,
+    ) -> (NoiseMap, Vec<[f32; 3]>, Vec<[f32; 3]>, Vec<[f32; 3]>) {
+        let map = self.build_map();
+        let (width, height) = self.size;
+        let (x_step, y_step) = self.cell_size();
+
+        let mut normals = Vec::with_capacity(width * height);
+        let mut tangents = Vec::with_capacity(width * height);
+        let mut bitangents = Vec::with_capacity(width * height);
+
+        let slope = |a: f64, b: f64, cells: usize, step: f64| {
+            if cells == 0 {
+                0.0
+            } else {
+                (b - a) * height_scale / (cells as f64 * step)
+            }
+        };
+
+        for y in 0..height {
+            let (y0, y1) = (y.saturating_sub(1), (y + 1).min(height - 1));
+
+            for x in 0..width {
+                let (x0, x1) = (x.saturating_sub(1), (x + 1).min(width - 1));
+
+                let dx = slope(map[(x0, y)], map[(x1, y)], x1 - x0, x_step);
+                let dz = slope(map[(x, y0)], map[(x, y1)], y1 - y0, y_step);
+
+                let normal = normalize([-dx, 1.0, -dz]);
+                let along_x = [1.0, dx, 0.0];
+                let tangent = normalize(sub(along_x, scale(normal, dot(normal, along_x))));
+                let bitangent = cross(tangent, normal);
+
+                normals.push(normal.map(|c| c as f32));
+                tangents.push(tangent.map(|c| c as f32));
+                bitangents.push(bitangent.map(|c| c as f32));
+            }
+        }
+
+        (map, normals, tangents, bitangents)
+    }
+
+    fn bounds(&self) -> Bounds {
+        (self.x_bounds, self.y_bounds)
+    }
+
+    fn sampling(&self) -> CellSampling {
+        CellSampling {
+            bounds: self.bounds(),
+            octaves: self.octaves,
+            layer: None,
+            #[cfg(feature = "std")]
+            deadline: None,
+        }
+    }
+
+    fn build_map(&self) -> NoiseMap {
+        self.build_checking_cancel(None)
+            .expect("uncancellable build returned no map")
+    }
+
+    fn build_checking_cancel(&self, cancel: Option<&AtomicBool>) -> Option<NoiseMap> {
+        let (width, height) = self.size;
+
+        let mut result_map = NoiseMap::new(width, height);
+        let completed_rows = AtomicUsize::new(0);
+
+        for (y, row) in result_map
+            .as_mut_slice()
+            .chunks_mut(width.max(1))
+            .enumerate()
+        {
+            if cancel.is_some_and(|cancel| cancel.load(Ordering::Relaxed)) {
+                return None;
+            }
+
+            self.fill_row(y, row);
+            self.finish_row(&completed_rows);
+        }
+
+        Some(result_map)
+    }
+
+    fn finish_row(&self, completed_rows: &AtomicUsize) {
+        let completed = completed_rows.fetch_add(1, Ordering::Relaxed) + 1;
+
+        self.report_progress(completed);
+    }
+
+    #[cfg(feature = "std")]
+    fn report_progress(&self, completed: usize) {
+        if let Some((sender, granularity)) = &self.progress {
+            let total = self.size.1;
+
+            if completed.is_multiple_of(*granularity) || completed == total {
+                // The receiver may have been dropped, in which case progress is simply discarded.
+                let _ = sender.send((completed, total));
+            }
+        }
+    }
+
+    #[cfg(not(feature = "std"))]
+    fn report_progress(&self, _completed: usize) {}
+
+    fn fill_row(&self, y: usize, row: &mut [f64]) {
+        for (x, value) in row.iter_mut().enumerate() {
+            *value = self.fill_cell(x as isize, y as isize);
+        }
+    }
+
+    /// Returns the final value of a cell. The compute mask only applies to cells inside the map.
+    fn fill_cell(&self, x: isize, y: isize) -> f64 {
+        self.fill_cell_within(self.sampling(), x, y)
+    }
+
+    /// Returns the final value of a cell sampled with `sampling`. When sampling a single octave
+    /// layer, cells excluded by the compute mask hold the fill value in the first layer and 0.0
+    /// in the others, and the output bit depth is not applied.
+    fn fill_cell_within(&self, sampling: CellSampling, x: isize, y: isize) -> f64 {
+        let (width, height) = self.size;
+        let inside = (0..width as isize).contains(&x) && (0..height as isize).contains(&y);
+
+        match (&self.compute_mask, sampling.layer) {
+            (Some(mask), layer) if inside && !mask(x as usize, y as usize) => match layer {
+                None | Some(0) => self.fill_value,
+                Some(_) => 0.0,
+            },
+            (_, None) => self.post_process(x, y, self.timed_sample_cell(sampling, x, y)),
+            (_, Some(_)) => self.timed_sample_cell(sampling, x, y) * self.island_factor(x, y).0,
+        }
+    }
+
+    /// Applies the island falloff and the output bit depth to a sampled value.
+    fn post_process(&self, x: isize, y: isize, sample: f64) -> f64 {
+        self.quantize(x, y, sample * self.island_factor(x, y).0)
+    }
+
+    /// Applies the output bit depth and dithering to a sampled value.
+    fn quantize(&self, x: isize, y: isize, value: f64) -> f64 {
+        const BAYER: [[f64; 4]; 4] = [
+            [0.0, 8.0, 2.0, 10.0],
+            [12.0, 4.0, 14.0, 6.0],
+            [3.0, 11.0, 1.0, 9.0],
+            [15.0, 7.0, 13.0, 5.0],
+        ];
+
+        let bits = match self.output_bits {
+            Some(bits) => bits,
+            None => return value,
+        };
+
+        let levels = ((1_u64 << bits) - 1) as f64;
+        let offset = match self.dither {
+            DitherMode::None => 0.0,
+            DitherMode::Bayer => {
+                (BAYER[y.rem_euclid(4) as usize][x.rem_euclid(4) as usize] + 0.5) / 16.0 - 0.5
+            }
+        };
+        let level = ((value * 0.5 + 0.5).clamp(0.0, 1.0) * levels + offset)
+            .round()
+            .clamp(0.0, levels);
+
+        level / levels * 2.0 - 1.0
+    }
+
+    /// Samples a cell, substituting the fill value if it exceeds the cell timeout.
+    #[cfg(feature = "std")]
+    fn timed_sample_cell(&self, sampling: CellSampling, x: isize, y: isize) -> f64 {
+        let timeout = match self.cell_timeout {
+            Some(timeout) => timeout,
+            None => return self.sample_cell(sampling, x, y),
+        };
+
+        let deadline = Instant::now() + timeout;
+        let sampling = CellSampling {
+            deadline: Some(deadline),
+            ..sampling
+        };
+        let value = self.sample_cell(sampling, x, y);
+
+        // Sampling stops early only once the deadline has passed, so this also catches every
+        // cell whose value is incomplete.
+        if Instant::now() > deadline {
+            self.timed_out_cells.fetch_add(1, Ordering::Relaxed);
+            self.fill_value
+        } else {
+            value
+        }
+    }
+
+    #[cfg(not(feature = "std"))]
+    fn timed_sample_cell(&self, sampling: CellSampling, x: isize, y: isize) -> f64 {
+        self.sample_cell(sampling, x, y)
+    }
+
+    #[cfg(feature = "std")]
+    fn is_past_deadline(sampling: CellSampling) -> bool {
+        sampling
+            .deadline
+            .is_some_and(|deadline| Instant::now() > deadline)
+    }
+
+    #[cfg(not(feature = "std"))]
+    fn is_past_deadline(_sampling: CellSampling) -> bool {
+        false
+    }
+
+    /// Returns the island falloff factor of a cell and its gradient in world units.
+    fn island_factor(&self, x: isize, y: isize) -> (f64, [f64; 2]) {
+        let power = match self.island_falloff {
+            Some(power) => power,
+            None => return (1.0, [0.0, 0.0]),
+        };
+
+        let (width, height) = self.size;
+        let (center_x, center_y) = (
+            width.saturating_sub(1) as f64 / 2.0,
+            height.saturating_sub(1) as f64 / 2.0,
+        );
+        let max_distance = (center_x * center_x + center_y * center_y).sqrt();
+
+        let (offset_x, offset_y) = (x as f64 - center_x, y as f64 - center_y);
+        let distance = (offset_x * offset_x + offset_y * offset_y).sqrt();
+
+        if max_distance == 0.0 {
+            return (1.0, [0.0, 0.0]);
+        }
+
+        let t = distance / max_distance;
+        let factor = 1.0 - t.powf(power);
+
+        if factor <= 0.0 {
+            return (0.0, [0.0, 0.0]);
+        }
+        if distance == 0.0 {
+            return (factor, [0.0, 0.0]);
+        }
+
+        let (x_step, y_step) = self.cell_size();
+        let slope = -power * t.powf(power - 1.0) / max_distance / distance;
+
+        (
+            factor,
+            [slope * offset_x / x_step, slope * offset_y / y_step],
+        )
+    }
+
+    fn sample_cell(&self, sampling: CellSampling, x: isize, y: isize) -> f64 {
+        let bounds = sampling.bounds;
+        let (x_bounds, y_bounds) = bounds;
+        let (seamless_x, seamless_y) = self.seamless_axes;
+
+        let x_extent = x_bounds.1 - x_bounds.0;
+        let y_extent = y_bounds.1 - y_bounds.0;
+
+        let (current_x, current_y) = self.cell_coords_within(bounds, x, y);
+        let seed = self
+            .seeded_sampler
+            .map_or(0, |(base_seed, _)| cell_seed(x, y, base_seed));
+
+        let blend_weight = |alpha: f64| match self.seamless_mode {
+            SeamlessMode::Linear => alpha,
+            SeamlessMode::Smoothstep => alpha.map_quintic(),
+        };
+        let x_alpha = (current_x - x_bounds.0) / x_extent;
+        let y_alpha = (current_y - y_bounds.0) / y_extent;
+
+        // Each sample is blended with its wrapped counterpart one extent earlier, so the blend
+        // is entirely the base sample on the lower bound and entirely the wrapped sample on the
+        // upper bound. Cells on either bound copy the sample at the lower bound directly rather
+        // than blending, so both edges of the map are bit-for-bit equal.
+        let sample_row = |point_y: f64| {
+            if !seamless_x {
+                self.sample_plane(sampling, current_x, point_y, seed)
+            } else if is_on_seam(x_alpha) {
+                self.sample_plane(sampling, x_bounds.0, point_y, seed)
+            } else {
+                let base_value = self.sample_plane(sampling, current_x, point_y, seed);
+                let wrapped_value =
+                    self.sample_plane(sampling, current_x - x_extent, point_y, seed);
+
+                interpolate::linear(base_value, wrapped_value, blend_weight(x_alpha))
+            }
+        };
+
+        if !seamless_y {
+            sample_row(current_y)
+        } else if is_on_seam(y_alpha) {
+            sample_row(y_bounds.0)
+        } else {
+            let base_value = sample_row(current_y);
+            let wrapped_value = sample_row(current_y - y_extent);
+
+            interpolate::linear(base_value, wrapped_value, blend_weight(y_alpha))
+        }
+    }
+
+    fn sample_plane(&self, sampling: CellSampling, x: f64, y: f64, seed: u64) -> f64 {
+        let (count, lacunarity, persistence) = sampling.octaves;
+        let (x, y) = self.warp_point(x, y);
+
+        let mut frequency = 1.0;
+        let mut amplitude = 1.0;
+        let mut total = 0.0;
+        let mut max_amplitude = 0.0;
+
+        for octave in 0..count {
+            if Self::is_past_deadline(sampling) {
+                break;
+            }
+
+            if sampling.layer.is_none_or(|layer| layer == octave) {
+                let value = match (self.tileable, self.torus_sampler) {
+                    (true, Some(get_torus)) => get_torus(
+                        &self.source_module,
+                        torus_point(sampling.bounds, x, y, frequency),
+                    ),
+                    _ => self.sample_source(self.source_point(x * frequency, y * frequency), seed),
+                };
+
+                total += amplitude * value;
+            }
+            max_amplitude += amplitude;
+
+            frequency *= lacunarity;
+            amplitude *= persistence;
+        }
+
+        total / max_amplitude
+    }
+
+    fn sample_source(&self, point: [f64; DIM], seed: u64) -> f64 {
+        match (self.seeded_sampler, self.fast_sampler) {
+            (Some((_, get_seeded)), _) => get_seeded(&self.source_module, point, seed),
+            (None, Some(get_f32)) => {
+                f64::from(get_f32(&self.source_module, point.map(|c| c as f32)))
+            }
+            (None, None) => self.source_module.get(point),
+        }
+    }
+
+    fn warp_point(&self, x: f64, y: f64) -> (f64, f64) {
+        match &self.domain_warp {
+            Some((warp_source, strength)) => {
+                let [offset_x, offset_y] = WARP_Y_OFFSET;
+
+                (
+                    x + strength * warp_source.get([x, y]),
+                    y + strength * warp_source.get([x + offset_x, y + offset_y]),
+                )
+            }
+            None => (x, y),
+        }
+    }
+
+    fn source_point(&self, x: f64, y: f64) -> [f64; DIM] {
+        match self.wrap_period {
+            Some((period_x, period_y)) => {
+                plane_point(x.rem_euclid(period_x), y.rem_euclid(period_y))
+            }
+            None => plane_point(x, y),
+        }
+    }
+}
+
+impl<SourceModule> NoiseMapBuilder<SourceModule> for PlaneMapBuilder<SourceModule, 3>
+where
+    SourceModule: NoiseFn<f64, 3>,
+{
+    fn set_size(self, width: usize, height: usize) -> Self {
+        PlaneMapBuilder {
+            cache: None,
+            size: (width, height),
+            ..self
+        }
+    }
+
+    fn set_source_module(self, source_module: SourceModule) -> Self {
+        PlaneMapBuilder {
+            cache: None,
+            source_module,
+            ..self
+        }
+    }
+
+    fn size(&self) -> (usize, usize) {
+        self.size
+    }
+
+    fn build(&self) -> NoiseMap {
+        self.build_map()
+    }
+
+    /// Samples every cell of the map without post-processing, including cells the compute mask
+    /// would skip, so that the mask can change between builds from the cache.
+    fn build_cache(&self) -> SampleCache {
+        let (width, height) = self.size;
+        let sampling = self.sampling();
+
+        SampleCache {
+            map: NoiseMap::from_fn(width, height, |x, y| {
+                self.timed_sample_cell(sampling, x as isize, y as isize)
+            }),
+        }
+    }
+
+    /// Applies this builder's compute mask, fill value, island falloff and output bit depth to
+    /// the cached samples. Sampling settings such as the bounds or octaves are not reapplied.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the cache was built with a different size.
+    fn build_from_cache(&self, cache: &SampleCache) -> NoiseMap {
+        assert_eq!(self.size, cache.map.size(), "noise map sizes must match");
+
+        let (width, height) = self.size;
+
+        NoiseMap::from_fn(width, height, |x, y| match &self.compute_mask {
+            Some(mask) if !mask(x, y) => self.fill_value,
+            _ => self.post_process(x as isize, y as isize, cache.map[(x, y)]),
+        })
+    }
+}
+
+impl<SourceFn, const DIM: usize> PlaneMapBuilder<NoiseFnWrapper<SourceFn, DIM>, DIM>
+where
+    SourceFn: Fn([f64; DIM]) -> f64,
+{
+    pub fn new_fn(source_fn: SourceFn) -> Self {
+        Self::new(NoiseFnWrapper { source_fn })
+    }
+
+    pub fn set_size(self, width: usize, height: usize) -> Self {
+        PlaneMapBuilder {
+            cache: None,
+            size: (width, height),
+            ..self
+        }
+    }
+}
+
+impl<SourceFn> PlaneMapBuilder<NoiseFnWrapper<SourceFn, 2>, 2>
+where
+    SourceFn: Fn([f64; 2]) -> f64,
+{
+    pub fn build(&self) -> NoiseMap {
+        self.build_map()
+    }
+}
+
+impl<SourceFn> PlaneMapBuilder<NoiseFnWrapper<SourceFn, 3>, 3>
+where
+    SourceFn: Fn([f64; 3]) -> f64,
+{
+    pub fn build(&self) -> NoiseMap {
+        self.build_map()
+    }
+}
+
+impl<SourceFn> PlaneMapBuilder<NoiseFnWrapper<SourceFn, 4>, 4>
+where
+    SourceFn: Fn([f64; 4]) -> f64,
+{
+    pub fn build(&self) -> NoiseMap {
+        self.build_map()
+    }
+}
+
+/// Returns whether a position's fraction of the way across a seamless axis lies on one of the
+/// axis' bounds, allowing for the rounding error of the sampling grid.
+fn is_on_seam(alpha: f64) -> bool {
+    const SEAM_TOLERANCE: f64 = 1e-9;
+
+    alpha.abs() < SEAM_TOLERANCE || (alpha - 1.0).abs() < SEAM_TOLERANCE
+}
+
+/// Returns the distance between samples along one axis.
+///
+/// Seamless axes sample both bounds inclusively so that the first and last cells see the same
+/// wrapped value, giving exact continuity when the map tiles.
+fn axis_step(bounds: (f64, f64), cells: usize, is_seamless: bool) -> f64 {
+    let extent = bounds.1 - bounds.0;
+
+    if is_seamless && cells > 1 {
+        extent / (cells - 1) as f64
+    } else {
+        extent / cells as f64
+    }
+}
+
+/// Mixes a cell's coordinates and a base seed into a well-distributed seed, using the SplitMix64
+/// finalizer.
+fn cell_seed(x: isize, y: isize, base_seed: u64) -> u64 {
+    let mut z = base_seed
+        ^ (x as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15)
+        ^ (y as u64).wrapping_mul(0xC2B2_AE3D_27D4_EB4F);
+
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+fn dot(a: [f64; 3], b: [f64; 3]) -> f64 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+fn sub(a: [f64; 3], b: [f64; 3]) -> [f64; 3] {
+    [a[0] - b[0], a[1] - b[1], a[2] - b[2]]
+}
+
+fn scale(a: [f64; 3], factor: f64) -> [f64; 3] {
+    a.map(|c| c * factor)
+}
+
+fn cross(a: [f64; 3], b: [f64; 3]) -> [f64; 3] {
+    [
+        a[1] * b[2] - a[2] * b[1],
+        a[2] * b[0] - a[0] * b[2],
+        a[0] * b[1] - a[1] * b[0],
+    ]
+}
+
+fn normalize(a: [f64; 3]) -> [f64; 3] {
+    scale(a, 1.0 / dot(a, a).sqrt())
+}
+
+/// Maps a plane point onto the torus used by
+/// [`set_tileable`](PlaneMapBuilder::set_tileable), scaled by `frequency`.
+fn torus_point(bounds: Bounds, x: f64, y: f64, frequency: f64) -> [f64; 4] {
+    let ((x0, x1), (y0, y1)) = bounds;
+    let (x_radius, y_radius) = ((x1 - x0) / TAU * frequency, (y1 - y0) / TAU * frequency);
+    let (u, v) = (TAU * (x - x0) / (x1 - x0), TAU * (y - y0) / (y1 - y0));
+
+    [
+        x_radius * u.cos(),
+        x_radius * u.sin(),
+        y_radius * v.cos(),
+        y_radius * v.sin(),
+    ]
+}
+
+/// Samples a source of at least four dimensions at a torus point, leaving any further
+/// components at zero.
+fn sample_torus_in_dim<SourceModule, const DIM: usize>(
+    source_module: &SourceModule,
+    torus: [f64; 4],
+) -> f64
+where
+    SourceModule: NoiseFn<f64, DIM>,
+{
+    let mut point = [0.0; DIM];
+    point[..4].copy_from_slice(&torus);
+
+    source_module.get(point)
+}
+
+/// Maps a position on the plane to a source point: `[x, y]`, `[x, y, 0.0]` or
+/// `[x, y, 0.0, 0.5]` depending on the dimension of the source.
+pub(super) fn plane_point<const DIM: usize>(x: f64, y: f64) -> [f64; DIM] {
+    let mut point = [0.0; DIM];
+
+    point[0] = x;
+    point[1] = y;
+    if DIM > 3 {
+        point[3] = 0.5;
+    }
+
+    point
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Constant, Perlin};
+
+    fn horizontal_seam_error(map: &NoiseMap) -> f64 {
+        let (width, height) = map.size();
+        (0..height)
+            .map(|y| (map[(width - 1, y)] - map[(0, y)]).abs())
+            .sum::<f64>()
+            / height as f64
+    }
+
+    fn vertical_seam_error(map: &NoiseMap) -> f64 {
+        let (width, height) = map.size();
+        (0..width)
+            .map(|x| (map[(x, height - 1)] - map[(x, 0)]).abs())
+            .sum::<f64>()
+            / width as f64
+    }
+
+    fn neighbor_delta(map: &NoiseMap) -> f64 {
+        let (width, height) = map.size();
+        let mut total = 0.0;
+        for y in 0..height {
+            for x in 1..width {
+                total += (map[(x, y)] - map[(x - 1, y)]).abs();
+            }
+        }
+        total / ((width - 1) * height) as f64
+    }
+
+    #[test]
+    fn x_only_seamless_blends_horizontal_seam() {
+        let map = PlaneMapBuilder::new(Perlin::new(3))
+            .set_size(64, 64)
+            .set_x_bounds(0.0, 4.0)
+            .set_y_bounds(0.0, 4.0)
+            .set_seamless_axes(true, false)
+            .build();
+
+        let delta = neighbor_delta(&map);
+        assert!(horizontal_seam_error(&map) < 2.0 * delta);
+        assert!(vertical_seam_error(&map) > 3.0 * delta);
+    }
+
+    #[test]
+    fn build_coords_match_sampling_grid() {
+        let builder = PlaneMapBuilder::new(Perlin::new(3))
+            .set_size(8, 4)
+            .set_x_bounds(-2.0, 2.0)
+            .set_y_bounds(1.0, 3.0);
+
+        let (x_map, y_map) = builder.build_coords();
+
+        for x in 0..8 {
+            assert_eq!(-2.0 + 0.5 * x as f64, x_map[(x, 2)]);
+            assert_eq!(1.0 + 0.5 * 2.0, y_map[(x, 2)]);
+        }
+    }
+
+    #[test]
+    fn subpixel_offset_shifts_samples() {
+        let builder = PlaneMapBuilder::new(Perlin::new(8))
+            .set_size(9, 9)
+            .set_x_bounds(-2.0, 2.0)
+            .set_y_bounds(-2.0, 2.0);
+
+        let baseline = builder.build();
+        let unshifted = PlaneMapBuilder::new(Perlin::new(8))
+            .set_size(9, 9)
+            .set_x_bounds(-2.0, 2.0)
+            .set_y_bounds(-2.0, 2.0)
+            .set_subpixel_offset(0.0, 0.0)
+            .build();
+        assert!(baseline.iter().eq(unshifted.iter()));
+
+        let (x_map, y_map) = builder.set_subpixel_offset(0.5, 0.5).build_coords();
+        for (x, y) in [(0, 0), (4, 7), (8, 3)] {
+            assert_eq!(-2.0 + (4.0 / 9.0) * (x as f64 + 0.5), x_map[(x, y)]);
+            assert_eq!(-2.0 + (4.0 / 9.0) * (y as f64 + 0.5), y_map[(x, y)]);
+        }
+    }
+
+    #[test]
+    fn fill_value_invalidates_cache() {
+        let mut builder = PlaneMapBuilder::new(Perlin::new(5)).set_size(6, 4);
+        let first = builder.build_cached();
+
+        let mut builder = builder.set_fill_value(7.0).set_compute_mask(|x, _y| x >= 3);
+        let masked = builder.build_cached();
+
+        for y in 0..4 {
+            for x in 0..3 {
+                assert_ne!(first[(x, y)], masked[(x, y)]);
+                assert_eq!(7.0, masked[(x, y)]);
+            }
+        }
+
+        // Only the fill value changes here, so this catches a setter that keeps the cache.
+        let mut builder = builder.set_fill_value(-3.0);
+        assert_eq!(-3.0, builder.build_cached()[(0, 0)]);
+    }
+
+    #[test]
+    fn build_cached_reuses_unchanged_builds() {
+        struct Counting<'a>(&'a AtomicUsize);
+
+        impl NoiseFn<f64, 3> for Counting<'_> {
+            fn get(&self, _point: [f64; 3]) -> f64 {
+                self.0.fetch_add(1, Ordering::Relaxed);
+                0.5
+            }
+        }
+
+        let samples = AtomicUsize::new(0);
+        let mut builder = PlaneMapBuilder::new(Counting(&samples)).set_size(4, 3);
+
+        let first = builder.build_cached();
+        assert_eq!(12, samples.load(Ordering::Relaxed));
+
+        let second = builder.build_cached();
+        assert_eq!(12, samples.load(Ordering::Relaxed));
+        assert!(first.iter().eq(second.iter()));
+
+        let mut builder = builder.set_x_bounds(0.0, 1.0);
+        builder.build_cached();
+        assert_eq!(24, samples.load(Ordering::Relaxed));
+    }
+
+    #[test]
+    fn mirror_folds_samples() {
+        let map = PlaneMapBuilder::new(Perlin::new(12))
+            .set_size(9, 6)
+            .set_x_bounds(-3.0, 3.0)
+            .set_y_bounds(-2.0, 2.0)
+            .set_mirror(true, false)
+            .build();
+
+        let (width, height) = map.size();
+        for y in 0..height {
+            for x in 0..width {
+                assert_eq!(map[(x, y)], map[(width - 1 - x, y)]);
+            }
+        }
+
+        assert!((0..width).any(|x| map[(x, 0)] != map[(x, height - 1)]));
+    }
+
+    #[test]
+    fn cell_size_and_world_bounds() {
+        let builder = PlaneMapBuilder::new(Perlin::new(3))
+            .set_size(8, 5)
+            .set_x_bounds(-2.0, 2.0)
+            .set_y_bounds(0.0, 10.0);
+
+        assert_eq!((0.5, 2.0), builder.cell_size());
+        assert_eq!(((-2.0, 2.0), (0.0, 10.0)), builder.world_bounds());
+
+        let seamless = builder.set_seamless_axes(false, true);
+        assert_eq!((0.5, 2.5), seamless.cell_size());
+        assert_eq!(((-2.0, 2.0), (0.0, 12.5)), seamless.world_bounds());
+    }
+
+    #[test]
+    fn island_falloff_fades_corners() {
+        let map = PlaneMapBuilder::new(crate::Constant::new(0.8))
+            .set_size(11, 11)
+            .set_island_falloff(2.0)
+            .build();
+
+        assert_eq!(0.8, map[(5, 5)]);
+        assert!((map[(4, 5)] - 0.8).abs() < 0.02);
+        assert_eq!(0.0, map[(0, 0)]);
+        assert_eq!(0.0, map[(10, 10)]);
+        assert!(map[(1, 1)] < map[(3, 3)]);
+    }
+
+    #[test]
+    fn wrap_period_tiles_periodic_sources() {
+        struct Waves;
+
+        impl NoiseFn<f64, 3> for Waves {
+            fn get(&self, [x, y, _]: [f64; 3]) -> f64 {
+                (x * core::f64::consts::PI).sin() * (y * core::f64::consts::PI).cos()
+            }
+        }
+
+        let tile = |x_bounds: (f64, f64)| {
+            PlaneMapBuilder::new(Waves)
+                .set_size(16, 8)
+                .set_x_bounds(x_bounds.0, x_bounds.1)
+                .set_y_bounds(0.0, 2.0)
+                .set_wrap_period(2.0, 2.0)
+                .build()
+        };
+
+        // The neighboring tile continues exactly where the first one ends.
+        let (first, next) = (tile((0.0, 4.0)), tile((4.0, 8.0)));
+        assert!(first.iter().eq(next.iter()));
+    }
+
+    #[test]
+    fn tile_with_margin_matches_full_build() {
+        let builder = PlaneMapBuilder::new(Perlin::new(14))
+            .set_size(16, 16)
+            .set_x_bounds(-2.0, 2.0)
+            .set_y_bounds(-2.0, 2.0);
+        let full = builder.build();
+
+        let tile = builder.build_tile_with_margin(1, 0, 8, 2);
+        assert_eq!((12, 12), tile.size());
+
+        for y in 0..8 {
+            for x in 0..8 {
+                assert_eq!(full[(8 + x, y)], tile[(2 + x, 2 + y)]);
+            }
+        }
+
+        // The left margin overlaps the neighboring tile.
+        assert_eq!(full[(6, 3)], tile[(0, 5)]);
+    }
+
+    #[test]
+    fn seamless_tile_margin_wraps_around() {
+        let builder = PlaneMapBuilder::new(Perlin::new(14))
+            .set_size(9, 9)
+            .set_is_seamless(true);
+
+        let first = builder.build_tile_with_margin(0, 0, 4, 2);
+        let last_column = builder.build_tile_with_margin(1, 0, 4, 2);
+        let last_row = builder.build_tile_with_margin(0, 1, 4, 2);
+
+        // Columns 8 and 9 of the map coincide with columns 0 and 1, so the right margin of the
+        // last tile repeats the first columns of tile 0, and likewise for rows.
+        for i in 0..2 {
+            for j in 0..8 {
+                assert_eq!(first[(2 + i, j)], last_column[(6 + i, j)]);
+                assert_eq!(first[(j, 2 + i)], last_row[(j, 6 + i)]);
+            }
+        }
+    }
+
+    #[test]
+    fn row_stagger_shifts_odd_rows() {
+        let builder = PlaneMapBuilder::new(Perlin::new(8))
+            .set_size(8, 4)
+            .set_x_bounds(0.0, 4.0)
+            .set_y_bounds(0.0, 2.0);
+
+        let unstaggered = PlaneMapBuilder::new(Perlin::new(8))
+            .set_size(8, 4)
+            .set_x_bounds(0.0, 4.0)
+            .set_y_bounds(0.0, 2.0)
+            .set_row_stagger(0.0)
+            .build();
+        assert!(builder.build().iter().eq(unstaggered.iter()));
+
+        let (x_map, _) = builder.set_row_stagger(0.5).build_coords();
+        for x in 0..8 {
+            assert_eq!(0.5 * x as f64, x_map[(x, 2)]);
+            assert_eq!(0.5 * (x as f64 + 0.5), x_map[(x, 3)]);
+        }
+    }
+
+    #[test]
+    fn fast_preview_uses_single_precision_path() {
+        struct Dual<'a>(&'a AtomicUsize);
+
+        impl NoiseFn<f64, 3> for Dual<'_> {
+            fn get(&self, point: [f64; 3]) -> f64 {
+                Perlin::new(2).get(point)
+            }
+        }
+
+        impl NoiseFnF32<3> for Dual<'_> {
+            fn get_f32(&self, point: [f32; 3]) -> f32 {
+                self.0.fetch_add(1, Ordering::Relaxed);
+                Perlin::new(2).get(point.map(f64::from)) as f32
+            }
+        }
+
+        let calls = AtomicUsize::new(0);
+        let precise = PlaneMapBuilder::new(Dual(&calls)).set_size(8, 8).build();
+        assert_eq!(0, calls.load(Ordering::Relaxed));
+
+        let builder = PlaneMapBuilder::new(Dual(&calls))
+            .set_size(8, 8)
+            .set_fast_preview(true);
+        assert!(builder.fast_preview());
+
+        let preview = builder.build();
+        assert_eq!(64, calls.load(Ordering::Relaxed));
+        assert!(precise
+            .iter()
+            .zip(preview.iter())
+            .all(|(a, b)| (a - b).abs() < 1e-5));
+    }
+
+    #[test]
+    fn fast_preview_with_builtin_source() {
+        let source = || crate::Fbm::<Perlin>::new(6);
+        let precise = PlaneMapBuilder::new(source()).set_size(8, 8).build();
+
+        let builder = PlaneMapBuilder::new(source())
+            .set_size(8, 8)
+            .set_fast_preview(true);
+        assert!(builder.fast_preview());
+
+        assert!(precise
+            .iter()
+            .zip(builder.build().iter())
+            .all(|(a, b)| (a - b).abs() < 1e-5));
+    }
+
+    #[test]
+    fn tbn_frames_are_orthonormal() {
+        let (map, normals, tangents, bitangents) = PlaneMapBuilder::new(crate::Constant::new(0.3))
+            .set_size(5, 4)
+            .build_with_tbn(2.0);
+
+        assert_eq!(20, normals.len());
+        assert!(map.iter().all(|&value| value == 0.3));
+        assert!(normals.iter().all(|&normal| normal == [0.0, 1.0, 0.0]));
+        assert!(tangents.iter().all(|&tangent| tangent == [1.0, 0.0, 0.0]));
+        assert!(bitangents
+            .iter()
+            .all(|&bitangent| bitangent == [0.0, 0.0, 1.0]));
+
+        let (_, normals, tangents, bitangents) = PlaneMapBuilder::new(Perlin::new(6))
+            .set_size(6, 6)
+            .build_with_tbn(3.0);
+        let dot = |a: [f32; 3], b: [f32; 3]| a[0] * b[0] + a[1] * b[1] + a[2] * b[2];
+
+        for i in 0..36 {
+            let (n, t, b) = (normals[i], tangents[i], bitangents[i]);
+
+            assert!(n[1] > 0.0);
+            for (u, v) in [(n, t), (t, b), (b, n)] {
+                assert!(dot(u, v).abs() < 1e-6);
+            }
+            for u in [n, t, b] {
+                assert!((dot(u, u) - 1.0).abs() < 1e-6);
+            }
+        }
+    }
+
+    #[test]
+    fn smoothstep_seams_keep_slope_continuous() {
+        let slope_mismatch = |mode| {
+            let map = PlaneMapBuilder::new(Perlin::new(17))
+                .set_size(256, 16)
+                .set_x_bounds(-2.0, 2.0)
+                .set_seamless_axes(true, false)
+                .set_seamless_mode(mode)
+                .build();
+            let (width, height) = map.size();
+
+            (0..height)
+                .map(|y| {
+                    let left = map[(1, y)] - map[(0, y)];
+                    let right = map[(width - 1, y)] - map[(width - 2, y)];
+                    (left - right).abs()
+                })
+                .sum::<f64>()
+        };
+
+        assert!(
+            slope_mismatch(SeamlessMode::Smoothstep) < 0.5 * slope_mismatch(SeamlessMode::Linear)
+        );
+    }
+
+    #[test]
+    fn cell_seeds_are_distinct_and_reproducible() {
+        struct SeedEcho;
+
+        impl NoiseFn<f64, 3> for SeedEcho {
+            fn get(&self, _point: [f64; 3]) -> f64 {
+                0.0
+            }
+        }
+
+        impl SeededNoiseFn<3> for SeedEcho {
+            fn get_seeded(&self, _point: [f64; 3], seed: u64) -> f64 {
+                (seed >> 11) as f64
+            }
+        }
+
+        let build = |base_seed| {
+            PlaneMapBuilder::new(SeedEcho)
+                .set_size(6, 6)
+                .set_cell_seed(base_seed)
+                .build()
+        };
+
+        let (first, again, other) = (build(7), build(7), build(8));
+        assert!(first.iter().eq(again.iter()));
+        assert!(first.iter().zip(other.iter()).all(|(a, b)| a != b));
+
+        let mut seeds: Vec<u64> = first.iter().map(|&value| value as u64).collect();
+        seeds.sort_unstable();
+        seeds.dedup();
+        assert_eq!(36, seeds.len());
+    }
+
+    #[test]
+    fn domain_warp_displaces_samples() {
+        let baseline = PlaneMapBuilder::new(Perlin::new(31))
+            .set_size(12, 12)
+            .build();
+
+        let unwarped = PlaneMapBuilder::new(Perlin::new(31))
+            .set_size(12, 12)
+            .set_domain_warp(Perlin::new(7), 0.0)
+            .build();
+        assert!(baseline.iter().eq(unwarped.iter()));
+
+        let warped = PlaneMapBuilder::new(Perlin::new(31))
+            .set_size(12, 12)
+            .set_domain_warp(Perlin::new(7), 0.5)
+            .build();
+        let changed = baseline
+            .iter()
+            .zip(warped.iter())
+            .filter(|(a, b)| (*a - *b).abs() > 1e-6)
+            .count();
+        assert!(changed > 72);
+
+        let shifted = PlaneMapBuilder::new(Perlin::new(31))
+            .set_size(12, 12)
+            .set_domain_warp(Constant::new(0.25), 1.0)
+            .build();
+        let translated = PlaneMapBuilder::new(Perlin::new(31))
+            .set_size(12, 12)
+            .set_x_bounds(-0.75, 1.25)
+            .set_y_bounds(-0.75, 1.25)
+            .build();
+        assert!(shifted
+            .iter()
+            .zip(translated.iter())
+            .all(|(a, b)| (a - b).abs() < 1e-12));
+    }
+
+    #[test]
+    fn thumbnail_averages_blocks_of_full_build() {
+        let builder = PlaneMapBuilder::new(Perlin::new(37)).set_size(10, 7);
+        let (map, thumbnail) = builder.build_with_thumbnail(4);
+
+        assert!(map.iter().eq(builder.build().iter()));
+        assert_eq!((4, 3), thumbnail.size());
+
+        for ty in 0..3 {
+            for tx in 0..4 {
+                let (mut sum, mut count) = (0.0, 0);
+                for y in ty * 3..(ty * 3 + 3).min(7) {
+                    for x in tx * 3..(tx * 3 + 3).min(10) {
+                        sum += map[(x, y)];
+                        count += 1;
+                    }
+                }
+
+                assert!((thumbnail[(tx, ty)] - sum / count as f64).abs() < 1e-12);
+            }
+        }
+    }
+
+    #[test]
+    fn tileable_maps_have_no_seams() {
+        let perlin = Perlin::new(41);
+        let tile = PlaneMapBuilder::new_fn(|point: [f64; 4]| perlin.get(point))
+            .set_size(8, 8)
+            .set_x_bounds(0.0, 3.0)
+            .set_y_bounds(1.0, 3.0)
+            .set_tileable(true)
+            .build_tile_with_margin(0, 0, 8, 1);
+
+        // The margin continues the grid one cell past each edge, so it must repeat the cells on
+        // the opposite side of the map.
+        for i in 0..10 {
+            assert!((tile[(0, i)] - tile[(8, i)]).abs() < 1e-12);
+            assert!((tile[(9, i)] - tile[(1, i)]).abs() < 1e-12);
+            assert!((tile[(i, 0)] - tile[(i, 8)]).abs() < 1e-12);
+            assert!((tile[(i, 9)] - tile[(i, 1)]).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn tileable_builds_with_plain_source() {
+        let build = |x_offset: f64| {
+            PlaneMapBuilder::new(Perlin::new(41))
+                .set_size(8, 8)
+                .set_x_bounds(x_offset, x_offset + 3.0)
+                .set_y_bounds(1.0, 3.0)
+                .set_tileable(true)
+                .build()
+        };
+        let map = build(0.0);
+
+        // Shifting the bounds by a whole extent moves around the torus back to the same points.
+        assert!(map
+            .iter()
+            .zip(build(3.0).iter())
+            .all(|(a, b)| (a - b).abs() < 1e-12));
+        assert!(neighbor_delta(&map) > 0.0);
+
+        let wrapped = PlaneMapBuilder::new(Perlin::new(41))
+            .set_size(8, 8)
+            .set_x_bounds(0.0, 3.0)
+            .set_y_bounds(1.0, 3.0)
+            .set_tileable(true)
+            .build_tile_with_margin(0, 0, 8, 1);
+        for i in 0..10 {
+            assert!((wrapped[(0, i)] - wrapped[(8, i)]).abs() < 1e-12);
+            assert!((wrapped[(i, 9)] - wrapped[(i, 1)]).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn cached_samples_take_new_post_processing() {
+        let builder = PlaneMapBuilder::new(Perlin::new(7)).set_size(12, 10);
+        let cache = builder.build_cache();
+
+        let processed = builder
+            .set_output_bits(2)
+            .set_island_falloff(2.0)
+            .set_compute_mask(|x, _y| x > 0)
+            .set_fill_value(-1.0);
+        let from_cache = processed.build_from_cache(&cache);
+
+        assert!(from_cache.iter().eq(processed.build().iter()));
+        assert!(!from_cache.iter().eq(cache.map().iter()));
+        assert_eq!(-1.0, from_cache[(0, 4)]);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn slow_cells_time_out_to_fill_value() {
+        struct SlowOnLeftEdge;
+
+        impl NoiseFn<f64, 3> for SlowOnLeftEdge {
+            fn get(&self, point: [f64; 3]) -> f64 {
+                if point[0] < -0.9 {
+                    std::thread::sleep(Duration::from_millis(100));
+                }
+                1.0
+            }
+        }
+
+        let builder = PlaneMapBuilder::new(SlowOnLeftEdge)
+            .set_size(4, 2)
+            .set_fill_value(-3.0)
+            .set_cell_timeout(Duration::from_millis(50));
+        let map = builder.build();
+
+        for y in 0..2 {
+            assert_eq!(-3.0, map[(0, y)]);
+            assert!((1..4).all(|x| map[(x, y)] == 1.0));
+        }
+        assert_eq!(2, builder.timed_out_cells());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn cell_timeout_stops_sampling_slow_cells() {
+        struct SlowOnLeftEdge<'a>(&'a AtomicUsize);
+
+        impl NoiseFn<f64, 3> for SlowOnLeftEdge<'_> {
+            fn get(&self, point: [f64; 3]) -> f64 {
+                if point[0] < -0.9 {
+                    self.0.fetch_add(1, Ordering::Relaxed);
+                    std::thread::sleep(Duration::from_millis(20));
+                }
+                1.0
+            }
+        }
+
+        let slow_calls = AtomicUsize::new(0);
+        // With a lacunarity of 1.0 every octave samples the same point, so each left-edge cell
+        // would make ten slow calls without the cutoff.
+        let map = PlaneMapBuilder::new(SlowOnLeftEdge(&slow_calls))
+            .set_size(4, 2)
+            .set_octaves(10, 1.0, 0.5)
+            .set_fill_value(-3.0)
+            .set_cell_timeout(Duration::from_millis(30))
+            .build();
+
+        assert_eq!(-3.0, map[(0, 0)]);
+        assert_eq!(-3.0, map[(0, 1)]);
+        assert!(slow_calls.load(Ordering::Relaxed) < 10);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn metadata_sidecar_reproduces_map() {
+        let builder = PlaneMapBuilder::new(Perlin::new(47))
+            .set_size(12, 9)
+            .set_x_bounds(-3.0, 1.0)
+            .set_y_bounds(0.5, 2.0)
+            .set_is_seamless(true)
+            .set_seamless_mode(SeamlessMode::Smoothstep)
+            .set_octaves(3, 2.1, 0.45)
+            .set_island_falloff(2.0)
+            .set_tileable(true);
+
+        let dir = std::env::temp_dir().join("plane_map_write_with_metadata");
+        std::fs::create_dir_all(&dir).unwrap();
+        let (image_path, meta_path) = (dir.join("map.ppm"), dir.join("map.json"));
+
+        builder
+            .write_with_metadata(&image_path, &meta_path)
+            .unwrap();
+
+        let config: PlaneMapConfig =
+            serde_json::from_reader(File::open(&meta_path).unwrap()).unwrap();
+        let reproduced = PlaneMapBuilder::new(Perlin::new(47))
+            .set_config(config)
+            .build();
+
+        assert_eq!(builder.config(), config);
+        assert!(config.tileable);
+        assert!(reproduced.iter().eq(builder.build().iter()));
+        assert!(!reproduced
+            .iter()
+            .eq(builder.set_tileable(false).build().iter()));
+        assert!(image_path.exists());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn context_border_surrounds_plain_build() {
+        let builder = PlaneMapBuilder::new(Perlin::new(53)).set_size(9, 6);
+        let (expanded, context) = builder.build_with_context(3);
+        let plain = builder.build();
+
+        assert_eq!(3, context);
+        assert_eq!((15, 12), expanded.size());
+        for y in 0..6 {
+            for x in 0..9 {
+                assert_eq!(plain[(x, y)], expanded[(x + 3, y + 3)]);
+            }
+        }
+
+        let (x_step, _) = builder.cell_size();
+        let shifted = PlaneMapBuilder::new(Perlin::new(53))
+            .set_size(9, 6)
+            .set_x_bounds(-1.0 - 3.0 * x_step, 1.0 - 3.0 * x_step)
+            .build();
+        assert!((expanded[(0, 5)] - shifted[(0, 2)]).abs() < 1e-12);
+    }
+
+    #[test]
+    fn seamless_context_wraps_around() {
+        let builder = PlaneMapBuilder::new(Perlin::new(53))
+            .set_size(9, 7)
+            .set_is_seamless(true);
+        let (expanded, context) = builder.build_with_context(2);
+        let plain = builder.build();
+
+        for y in 0..7 {
+            assert_eq!(plain[(7, y)], expanded[(context - 1, y + context)]);
+            assert_eq!(plain[(1, y)], expanded[(context + 9, y + context)]);
+        }
+        for x in 0..9 {
+            assert_eq!(plain[(x, 5)], expanded[(x + context, context - 1)]);
+            assert_eq!(plain[(x, 1)], expanded[(x + context, context + 7)]);
+        }
+    }
+
+    #[test]
+    fn output_bits_quantize_values() {
+        let builder = PlaneMapBuilder::new(Perlin::new(61)).set_size(16, 16);
+        let plain = builder.build();
+
+        let eight_bit = PlaneMapBuilder::new(Perlin::new(61))
+            .set_size(16, 16)
+            .set_output_bits(8)
+            .build();
+        assert!(plain
+            .iter()
+            .zip(eight_bit.iter())
+            .all(|(a, b)| (a - b).abs() <= 1.0 / 255.0 + 1e-12));
+
+        let dithered = PlaneMapBuilder::new(Perlin::new(61))
+            .set_size(16, 16)
+            .set_output_bits(2)
+            .set_dither(DitherMode::Bayer)
+            .build();
+        let mut levels = [false; 4];
+        for &value in dithered.iter() {
+            let level = (value + 1.0) / 2.0 * 3.0;
+
+            assert!((level - level.round()).abs() < 1e-9);
+            levels[level.round() as usize] = true;
+        }
+        assert!(levels.iter().filter(|&&seen| seen).count() >= 2);
+        assert!(plain
+            .iter()
+            .zip(dithered.iter())
+            .all(|(a, b)| (a - b).abs() <= 2.0 / 3.0 + 1e-12));
+    }
+
+    #[test]
+    fn octave_layers_sum_to_combined_build() {
+        let builder = PlaneMapBuilder::new(Perlin::new(71))
+            .set_size(12, 10)
+            .set_is_seamless(true);
+        let layers = builder.build_octave_layers(4, 2.0, 0.5);
+
+        let combined = PlaneMapBuilder::new(Perlin::new(71))
+            .set_size(12, 10)
+            .set_is_seamless(true)
+            .set_octaves(4, 2.0, 0.5)
+            .build();
+
+        assert_eq!(4, layers.len());
+        for y in 0..10 {
+            for x in 0..12 {
+                let sum: f64 = layers.iter().map(|layer| layer[(x, y)]).sum();
+
+                assert!((sum - combined[(x, y)]).abs() < 1e-12);
+            }
+        }
+    }
+
+    #[test]
+    fn row_ranges_concatenate_to_full_build() {
+        let builder = PlaneMapBuilder::new(Perlin::new(29))
+            .set_size(9, 8)
+            .set_row_stagger(0.5);
+
+        let top = builder.build_rows(0, 3);
+        let bottom = builder.build_rows(3, 8);
+
+        assert_eq!((9, 3), top.size());
+        assert!(top.concat_v(&bottom).iter().eq(builder.build().iter()));
+        assert_eq!(0, builder.build_rows(8, 8).size().1);
+    }
+
+    #[test]
+    fn animated_bounds_end_on_the_end_bounds() {
+        let builder = PlaneMapBuilder::new(Perlin::new(23))
+            .set_size(8, 6)
+            .set_x_bounds(-1.0, 1.0)
+            .set_y_bounds(-1.0, 1.0);
+        let frames = builder.build_animated_bounds((2.0, 5.0), (0.5, 3.0), 4);
+
+        let last = PlaneMapBuilder::new(Perlin::new(23))
+            .set_size(8, 6)
+            .set_x_bounds(2.0, 5.0)
+            .set_y_bounds(0.5, 3.0)
+            .build();
+
+        assert_eq!(4, frames.len());
+        assert!(frames[0].iter().eq(builder.build().iter()));
+        assert!(frames[3].iter().eq(last.iter()));
+        assert!(builder
+            .build_animated_bounds((2.0, 5.0), (0.5, 3.0), 0)
+            .is_empty());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn tiles_written_to_dir_reassemble_full_build() {
+        use core::convert::TryInto;
+
+        let builder = PlaneMapBuilder::new(Perlin::new(19)).set_size(10, 7);
+        let dir = std::env::temp_dir().join("plane_map_build_tiles_to_dir");
+        std::fs::create_dir_all(&dir).unwrap();
+
+        builder.build_tiles_to_dir(&dir, 4).unwrap();
+
+        let full = builder.build();
+        let mut reassembled = NoiseMap::new(10, 7);
+
+        for tile_y in 0..2 {
+            for tile_x in 0..3 {
+                let path = dir.join(std::format!("tile_{}_{}.raw", tile_x, tile_y));
+                let bytes = std::fs::read(&path).unwrap();
+                let tile_width = 4.min(10 - tile_x * 4);
+
+                for (i, chunk) in bytes.chunks(8).enumerate() {
+                    let value = f64::from_le_bytes(chunk.try_into().unwrap());
+                    reassembled[(tile_x * 4 + i % tile_width, tile_y * 4 + i / tile_width)] = value;
+                }
+            }
+        }
+
+        assert!(full.iter().eq(reassembled.iter()));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn combined_sources_build_through_builder() {
+        use crate::{Add, Clamp, Constant, Multiply, ScaleBias};
+
+        let perlin = Perlin::new(5);
+        let scaled = ScaleBias::new(perlin).set_scale(2.0).set_bias(0.5);
+        let sum = Add::new(scaled.clone(), Constant::new(0.25));
+        let product = Multiply::new(sum, Constant::new(-2.0));
+        let clamped = Clamp::new(product).set_bounds(-1.5, 1.5);
+
+        let map = PlaneMapBuilder::new(&clamped)
+            .set_size(8, 8)
+            .set_x_bounds(0.0, 2.0)
+            .set_y_bounds(0.0, 2.0)
+            .build();
+        let (x_map, y_map) = PlaneMapBuilder::new(&clamped)
+            .set_size(8, 8)
+            .set_x_bounds(0.0, 2.0)
+            .set_y_bounds(0.0, 2.0)
+            .build_coords();
+
+        for (x, y) in [(0, 0), (3, 5), (7, 2)] {
+            let point = [x_map[(x, y)], y_map[(x, y)], 0.0];
+            let expected = ((perlin.get(point) * 2.0 + 0.5 + 0.25) * -2.0).clamp(-1.5, 1.5);
+
+            assert!((map[(x, y)] - expected).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn build_cancellable_stops_when_flag_set() {
+        let cancel = Arc::new(AtomicBool::new(false));
+        let calls = AtomicUsize::new(0);
+        let builder = PlaneMapBuilder::new_fn(|point: [f64; 2]| {
+            if calls.fetch_add(1, Ordering::Relaxed) == 50 {
+                cancel.store(true, Ordering::Relaxed);
+            }
+            point[0] * point[1]
+        })
+        .set_size(16, 16);
+
+        assert!(builder.build_cancellable(cancel.clone()).is_none());
+        assert!(calls.load(Ordering::Relaxed) < 16 * 16);
+
+        cancel.store(false, Ordering::Relaxed);
+        let map = builder.build_cancellable(cancel.clone()).unwrap();
+        assert!(map.iter().eq(builder.build().iter()));
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn par_build_cancellable_stops_when_flag_set() {
+        let cancel = Arc::new(AtomicBool::new(false));
+        let builder = PlaneMapBuilder::new_fn(|point: [f64; 2]| {
+            cancel.store(true, Ordering::Relaxed);
+            point[0]
+        })
+        .set_size(16, 256);
+
+        assert!(builder.par_build_cancellable(cancel.clone()).is_none());
+
+        let builder = PlaneMapBuilder::new(Perlin::new(1)).set_size(16, 64);
+        let map = builder
+            .par_build_cancellable(Arc::new(AtomicBool::new(false)))
+            .unwrap();
+        assert!(map.iter().eq(builder.build().iter()));
+    }
+
+    #[test]
+    fn seamless_edges_match() {
+        let map = PlaneMapBuilder::new(Perlin::new(8))
+            .set_size(33, 17)
+            .set_x_bounds(-1.5, 2.5)
+            .set_y_bounds(0.0, 3.0)
+            .set_is_seamless(true)
+            .build();
+
+        for y in 0..17 {
+            assert!((map[(0, y)] - map[(32, y)]).abs() < 1e-12);
+        }
+        for x in 0..33 {
+            assert!((map[(x, 0)] - map[(x, 16)]).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn seamless_edges_copy_lower_bound_sample() {
+        let source = Perlin::new(8);
+        let map = PlaneMapBuilder::new(source)
+            .set_size(33, 17)
+            .set_x_bounds(-1.5, 2.5)
+            .set_y_bounds(0.1, 3.0)
+            .set_seamless_axes(true, false)
+            .build();
+
+        for y in 0..17 {
+            let current_y = 0.1 + (3.0 - 0.1) / 17.0 * y as f64;
+            let expected = source.get([-1.5, current_y, 0.0]);
+
+            assert_eq!(expected, map[(0, y)]);
+            assert_eq!(expected, map[(32, y)]);
+        }
+    }
+
+    #[test]
+    fn ranges_match_bounds() {
+        let from_range = PlaneMapBuilder::new(Perlin::new(2))
+            .set_size(16, 16)
+            .set_x_range(-2.0..2.0)
+            .set_y_range(0.5..1.5)
+            .build();
+        let from_bounds = PlaneMapBuilder::new(Perlin::new(2))
+            .set_size(16, 16)
+            .set_x_bounds(-2.0, 2.0)
+            .set_y_bounds(0.5, 1.5)
+            .build();
+
+        assert!(from_range.iter().eq(from_bounds.iter()));
+    }
+
+    struct Paraboloid;
+
+    impl NoiseFn<f64, 3> for Paraboloid {
+        fn get(&self, point: [f64; 3]) -> f64 {
+            point[0] * point[0] + 3.0 * point[1]
+        }
+    }
+
+    impl NoiseFnDeriv<3> for Paraboloid {
+        fn get_with_derivative(&self, point: [f64; 3]) -> (f64, [f64; 3]) {
+            (self.get(point), [2.0 * point[0], 3.0, 0.0])
+        }
+    }
+
+    #[test]
+    fn build_with_derivatives_uses_source_gradient() {
+        let builder = PlaneMapBuilder::new(Paraboloid)
+            .set_size(8, 8)
+            .set_x_bounds(-2.0, 2.0)
+            .set_y_bounds(-1.0, 1.0);
+
+        let (values, dx, dy) = builder.build_with_derivatives();
+        let (x_map, _) = builder.build_coords();
+
+        assert!(values.iter().eq(builder.build().iter()));
+        for y in 0..8 {
+            for x in 0..8 {
+                assert_eq!(2.0 * x_map[(x, y)], dx[(x, y)]);
+                assert_eq!(3.0, dy[(x, y)]);
+            }
+        }
+    }
+
+    #[test]
+    fn fill_value_applies_to_every_build() {
+        let builder = PlaneMapBuilder::new(Paraboloid)
+            .set_size(6, 6)
+            .set_compute_mask(|x, y| x != y)
+            .set_fill_value(-1.0);
+
+        let (values, dx, dy) = builder.build_with_derivatives();
+        let bounded = builder.build_bounded();
+
+        for i in 0..6 {
+            assert_eq!(-1.0, values[(i, i)]);
+            assert_eq!(-1.0, bounded.map()[(i, i)]);
+            assert_eq!((0.0, 0.0), (dx[(i, i)], dy[(i, i)]));
+        }
+    }
+
+    #[test]
+    fn derivatives_of_builtin_source() {
+        let perlin = Perlin::new(17);
+        let builder = PlaneMapBuilder::new(perlin).set_size(8, 6);
+
+        let (values, dx, dy) = builder.build_with_derivatives();
+        let (x_map, y_map) = builder.build_coords();
+
+        assert!(values.iter().eq(builder.build().iter()));
+        for y in 0..6 {
+            for x in 0..8 {
+                let point = [x_map[(x, y)], y_map[(x, y)], 0.0];
+                let (_, derivative) = perlin.get_with_derivative(point);
+
+                assert_eq!(derivative[0], dx[(x, y)]);
+                assert_eq!(derivative[1], dy[(x, y)]);
+            }
+        }
+        assert!(dx.iter().any(|&slope| slope.abs() > 1e-3));
+    }
+
+    #[test]
+    fn default_derivative_uses_finite_differences() {
+        struct Plane;
+
+        impl NoiseFn<f64, 2> for Plane {
+            fn get(&self, point: [f64; 2]) -> f64 {
+                0.5 * point[0] - 2.0 * point[1]
+            }
+        }
+
+        impl NoiseFnDeriv<2> for Plane {}
+
+        let (value, derivative) = Plane.get_with_derivative([1.0, 1.0]);
+
+        assert_eq!(-1.5, value);
+        assert!((derivative[0] - 0.5).abs() < 1e-9);
+        assert!((derivative[1] + 2.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn compute_mask_skips_cells() {
+        let calls = AtomicUsize::new(0);
+        let map = PlaneMapBuilder::new_fn(|point: [f64; 2]| {
+            calls.fetch_add(1, Ordering::Relaxed);
+            point[0] + 10.0
+        })
+        .set_size(8, 4)
+        .set_compute_mask(|x, _| x < 4)
+        .set_fill_value(-1.0)
+        .build();
+
+        assert_eq!(4 * 4, calls.load(Ordering::Relaxed));
+        for y in 0..4 {
+            for x in 0..8 {
+                if x < 4 {
+                    assert!(map[(x, y)] > 5.0);
+                } else {
+                    assert_eq!(-1.0, map[(x, y)]);
+                }
+            }
+        }
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn progress_channel_reaches_completion() {
+        let mut builder = PlaneMapBuilder::new(Perlin::new(1)).set_size(8, 10);
+        let receiver = builder.set_progress_channel(3);
+
+        builder.build();
+        let progress: Vec<_> = receiver.try_iter().collect();
+
+        assert_eq!(vec![(3, 10), (6, 10), (9, 10), (10, 10)], progress);
+    }
+
+    #[test]
+    fn octaves_add_detail() {
+        let builder = PlaneMapBuilder::new(Perlin::new(12))
+            .set_size(64, 64)
+            .set_x_bounds(0.0, 2.0)
+            .set_y_bounds(0.0, 2.0);
+
+        let plain = builder.build();
+        let builder = builder.set_octaves(1, 2.0, 0.5);
+        assert!(builder.build().iter().eq(plain.iter()));
+
+        let detailed = builder.set_octaves(6, 2.0, 0.5).build();
+        assert!(neighbor_delta(&detailed) > neighbor_delta(&plain));
     }
 }
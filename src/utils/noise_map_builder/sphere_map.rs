@@ -1,14 +1,33 @@
 use crate::{utils::NoiseMap, NoiseFn};
+use core::f64::consts::{FRAC_PI_2, PI};
 
 use super::NoiseMapBuilder;
 
+/// The unit in which [`SphereMapBuilder`] latitude and longitude bounds are given.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum AngleUnit {
+    #[default]
+    Degrees,
+    Radians,
+}
+
+/// Builds a map by sampling the source module on the surface of a unit sphere.
+///
+/// Bounds are in [degrees](AngleUnit::Degrees) by default and cover the whole globe: latitudes
+/// from -90 to 90 and longitudes from -180 to 180.
+///
+/// Unless [disabled](SphereMapBuilder::set_clamp_bounds), latitude bounds are clamped to the
+/// poles and longitude bounds are shifted to start within one turn of the prime meridian at build
+/// time.
 pub struct SphereMapBuilder<SourceModule>
 where
     SourceModule: NoiseFn<f64, 3>,
 {
+    angle_unit: AngleUnit,
     latitude_bounds: (f64, f64),
     longitude_bounds: (f64, f64),
     size: (usize, usize),
+    clamp_bounds: bool,
     source_module: SourceModule,
 }
 
@@ -18,13 +37,24 @@ where
 {
     pub fn new(source_module: SourceModule) -> Self {
         SphereMapBuilder {
-            latitude_bounds: (-1.0, 1.0),
-            longitude_bounds: (-1.0, 1.0),
+            angle_unit: AngleUnit::Degrees,
+            latitude_bounds: (-90.0, 90.0),
+            longitude_bounds: (-180.0, 180.0),
             size: (100, 100),
+            clamp_bounds: true,
             source_module,
         }
     }
 
+    /// Sets the unit in which the latitude and longitude bounds are interpreted.
+    pub fn set_angle_unit(self, angle_unit: AngleUnit) -> Self {
+        SphereMapBuilder { angle_unit, ..self }
+    }
+
+    pub fn angle_unit(&self) -> AngleUnit {
+        self.angle_unit
+    }
+
     pub fn set_latitude_bounds(self, min_lat_bound: f64, max_lat_bound: f64) -> Self {
         SphereMapBuilder {
             latitude_bounds: (min_lat_bound, max_lat_bound),
@@ -53,6 +83,22 @@ where
         }
     }
 
+    /// Enables or disables the build-time normalization of the bounds. The default is `true`.
+    ///
+    /// When enabled, latitude bounds beyond the poles are clamped to ±90°, and the longitude
+    /// bounds are shifted by whole turns so that the lower bound lies in `[-180°, 180°)`, with
+    /// the extent limited to one full turn.
+    pub fn set_clamp_bounds(self, clamp_bounds: bool) -> Self {
+        SphereMapBuilder {
+            clamp_bounds,
+            ..self
+        }
+    }
+
+    pub fn clamp_bounds(&self) -> bool {
+        self.clamp_bounds
+    }
+
     pub fn latitude_bounds(&self) -> (f64, f64) {
         self.latitude_bounds
     }
@@ -89,17 +135,42 @@ where
 
         let mut result_map = NoiseMap::new(width, height);
 
-        let lon_extent = self.longitude_bounds.1 - self.longitude_bounds.0;
-        let lat_extent = self.latitude_bounds.1 - self.latitude_bounds.0;
+        let to_radians = |bounds: (f64, f64)| match self.angle_unit {
+            AngleUnit::Degrees => (bounds.0.to_radians(), bounds.1.to_radians()),
+            AngleUnit::Radians => bounds,
+        };
+        let mut latitude_bounds = to_radians(self.latitude_bounds);
+        let mut longitude_bounds = to_radians(self.longitude_bounds);
+
+        if self.clamp_bounds {
+            let clamped = (
+                latitude_bounds.0.clamp(-FRAC_PI_2, FRAC_PI_2),
+                latitude_bounds.1.clamp(-FRAC_PI_2, FRAC_PI_2),
+            );
+
+            #[cfg(all(feature = "std", debug_assertions))]
+            if clamped != latitude_bounds {
+                eprintln!(
+                    "SphereMapBuilder: latitude bounds {:?} clamped to the poles",
+                    self.latitude_bounds
+                );
+            }
+
+            latitude_bounds = clamped;
+            longitude_bounds = normalize_longitude(longitude_bounds);
+        }
+
+        let lon_extent = longitude_bounds.1 - longitude_bounds.0;
+        let lat_extent = latitude_bounds.1 - latitude_bounds.0;
 
         let x_step = lon_extent / width as f64;
         let y_step = lat_extent / height as f64;
 
         for y in 0..height {
-            let current_lat = self.latitude_bounds.0 + y_step * y as f64;
+            let current_lat = latitude_bounds.0 + y_step * y as f64;
 
             for x in 0..width {
-                let current_lon = self.longitude_bounds.0 + x_step * x as f64;
+                let current_lon = longitude_bounds.0 + x_step * x as f64;
 
                 let point = lat_lon_to_xyz(current_lat, current_lon);
 
@@ -111,11 +182,70 @@ where
     }
 }
 
+/// Shifts longitude bounds, in radians, by whole turns so the lower bound lies in `[-PI, PI)`,
+/// and limits the extent to a single turn.
+fn normalize_longitude((lower, upper): (f64, f64)) -> (f64, f64) {
+    let turn = 2.0 * PI;
+    let shifted = (lower + PI).rem_euclid(turn) - PI;
+    let extent = (upper - lower).clamp(-turn, turn);
+
+    (shifted, shifted + extent)
+}
+
+/// Converts a latitude and longitude, in radians, to a point on the unit sphere.
 fn lat_lon_to_xyz(lat: f64, lon: f64) -> [f64; 3] {
-    let r = lat.to_radians().cos();
-    let x = r * lon.to_radians().cos();
-    let y = lat.to_radians().sin();
-    let z = r * lon.to_radians().sin();
+    let r = lat.cos();
+    let x = r * lon.cos();
+    let y = lat.sin();
+    let z = r * lon.sin();
 
     [x, y, z]
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Perlin;
+    use core::f64::consts::FRAC_PI_4;
+
+    #[test]
+    fn degree_and_radian_bounds_match() {
+        let degrees = SphereMapBuilder::new(Perlin::new(6))
+            .set_size(32, 16)
+            .set_bounds(-90.0, 45.0, -180.0, 90.0)
+            .build();
+        let radians = SphereMapBuilder::new(Perlin::new(6))
+            .set_size(32, 16)
+            .set_angle_unit(AngleUnit::Radians)
+            .set_bounds(-FRAC_PI_2, FRAC_PI_4, -PI, FRAC_PI_2)
+            .build();
+
+        assert!(degrees
+            .iter()
+            .zip(radians.iter())
+            .all(|(a, b)| (a - b).abs() < 1e-9));
+    }
+
+    #[test]
+    fn latitude_is_clamped_to_poles() {
+        let pole = SphereMapBuilder::new(Perlin::new(6))
+            .set_size(16, 4)
+            .set_latitude_bounds(90.0, 120.0)
+            .build();
+
+        let pole_value = Perlin::new(6).get(lat_lon_to_xyz(FRAC_PI_2, 0.0));
+        assert!(pole.iter().all(|value| (value - pole_value).abs() < 1e-9));
+
+        let clamped = SphereMapBuilder::new(Perlin::new(6))
+            .set_size(16, 8)
+            .set_bounds(-90.0, 120.0, 180.0, 540.0)
+            .build();
+        let whole_globe = SphereMapBuilder::new(Perlin::new(6))
+            .set_size(16, 8)
+            .build();
+        assert!(clamped
+            .iter()
+            .zip(whole_globe.iter())
+            .all(|(a, b)| (a - b).abs() < 1e-9));
+    }
+}
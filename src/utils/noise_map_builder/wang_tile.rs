@@ -0,0 +1,230 @@
+use crate::{utils::NoiseMap, NoiseFn};
+use alloc::vec::Vec;
+use core::f64::consts::PI;
+
+use super::plane_point;
+
+/// The edge colors of a Wang tile, each an index into the builder's edge colors.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct WangEdges {
+    pub north: usize,
+    pub east: usize,
+    pub south: usize,
+    pub west: usize,
+}
+
+/// Builds sets of Wang tiles whose edges match whenever their edge colors match.
+///
+/// Every edge color is assigned a world-space offset into the source module. Near an edge, a
+/// tile is sampled from the field belonging to that edge's color, laid out so that the
+/// neighboring tile across the edge sees the same field. The edge fields fade to a shared value
+/// at the corners, and the interior of each tile is blended with a field unique to the tile.
+/// Any arrangement in which touching edges share a color therefore tiles without seams.
+///
+/// Tiles are `tile_size` x `tile_size` cells with row 0 on the north edge and column 0 on the
+/// west edge. Edge cells lie exactly on the tile boundary, so neighboring tiles share their
+/// border rows and columns.
+pub struct WangTileBuilder<SourceModule, const DIM: usize>
+where
+    SourceModule: NoiseFn<f64, DIM>,
+{
+    edge_colors: Vec<[f64; 2]>,
+    tile_size: usize,
+    tile_extent: f64,
+    source_module: SourceModule,
+}
+
+impl<SourceModule, const DIM: usize> WangTileBuilder<SourceModule, DIM>
+where
+    SourceModule: NoiseFn<f64, DIM>,
+{
+    pub fn new(source_module: SourceModule) -> Self {
+        WangTileBuilder {
+            edge_colors: vec![[0.0, 0.0], [173.0, -311.0]],
+            tile_size: 64,
+            tile_extent: 1.0,
+            source_module,
+        }
+    }
+
+    /// Sets the world-space sampling offset of each edge color.
+    pub fn set_edge_colors(self, edge_colors: Vec<[f64; 2]>) -> Self {
+        WangTileBuilder {
+            edge_colors,
+            ..self
+        }
+    }
+
+    /// Sets the number of cells along each side of a tile.
+    pub fn set_tile_size(self, tile_size: usize) -> Self {
+        WangTileBuilder { tile_size, ..self }
+    }
+
+    /// Sets the world-space width and height covered by a single tile.
+    pub fn set_tile_extent(self, tile_extent: f64) -> Self {
+        WangTileBuilder {
+            tile_extent,
+            ..self
+        }
+    }
+
+    pub fn edge_colors(&self) -> &[[f64; 2]] {
+        &self.edge_colors
+    }
+
+    pub fn tile_size(&self) -> usize {
+        self.tile_size
+    }
+
+    pub fn tile_extent(&self) -> f64 {
+        self.tile_extent
+    }
+
+    /// Builds the tile with the given edge colors.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any edge refers to a color that does not exist.
+    pub fn build_tile(&self, edges: WangEdges) -> NoiseMap {
+        let color_count = self.edge_colors.len();
+        let [north, east, south, west] =
+            [edges.north, edges.east, edges.south, edges.west].map(|color| {
+                assert!(color < color_count, "unknown Wang edge color {}", color);
+                self.edge_colors[color]
+            });
+
+        let tile_index = ((edges.north * color_count + edges.east) * color_count + edges.south)
+            * color_count
+            + edges.west;
+        let interior = [
+            -1000.0 - 97.3 * tile_index as f64,
+            1000.0 + 61.7 * tile_index as f64,
+        ];
+
+        let corner_value = self.sample(self.edge_colors[0], -0.5, -0.5);
+        let denominator = self.tile_size.saturating_sub(1).max(1) as f64;
+
+        let mut result_map = NoiseMap::new(self.tile_size, self.tile_size);
+
+        for y in 0..self.tile_size {
+            let v = y as f64 / denominator;
+
+            for x in 0..self.tile_size {
+                let u = x as f64 / denominator;
+
+                let blend_edge = |offset, local_u, local_v, along: f64| {
+                    let window = (PI * along).sin();
+                    let value = self.sample(offset, local_u, local_v);
+
+                    corner_value + window * (value - corner_value)
+                };
+
+                let fields = [
+                    (v, blend_edge(north, u, v, u)),
+                    (1.0 - u, blend_edge(east, u - 1.0, v, v)),
+                    (1.0 - v, blend_edge(south, u, v - 1.0, u)),
+                    (u, blend_edge(west, u, v, v)),
+                ];
+
+                result_map[(x, y)] = if fields.iter().any(|(distance, _)| *distance <= 0.0) {
+                    let on_edge = fields.iter().filter(|(distance, _)| *distance <= 0.0);
+                    let (sum, count) = on_edge.fold((0.0, 0.0), |(sum, count), (_, value)| {
+                        (sum + value, count + 1.0)
+                    });
+
+                    sum / count
+                } else {
+                    // Inverse-distance weighting: edge fields dominate near their edge, and the
+                    // tile's own interior field dominates near the center.
+                    let interior_weight = 16.0;
+                    let mut total = interior_weight * self.sample(interior, u, v);
+                    let mut total_weight = interior_weight;
+
+                    for (distance, value) in fields {
+                        let weight = 1.0 / (distance * distance);
+
+                        total += weight * value;
+                        total_weight += weight;
+                    }
+
+                    total / total_weight
+                };
+            }
+        }
+
+        result_map
+    }
+
+    /// Builds every combination of edge colors, one tile per combination.
+    pub fn build_all(&self) -> Vec<(WangEdges, NoiseMap)> {
+        let colors = self.edge_colors.len();
+        let mut tiles = Vec::with_capacity(colors.pow(4));
+
+        for north in 0..colors {
+            for east in 0..colors {
+                for south in 0..colors {
+                    for west in 0..colors {
+                        let edges = WangEdges {
+                            north,
+                            east,
+                            south,
+                            west,
+                        };
+
+                        tiles.push((edges, self.build_tile(edges)));
+                    }
+                }
+            }
+        }
+
+        tiles
+    }
+
+    fn sample(&self, offset: [f64; 2], u: f64, v: f64) -> f64 {
+        self.source_module.get(plane_point(
+            offset[0] + u * self.tile_extent,
+            offset[1] + v * self.tile_extent,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Perlin;
+
+    #[test]
+    fn shared_edge_colors_match() {
+        let builder = WangTileBuilder::<_, 3>::new(Perlin::new(21))
+            .set_tile_size(17)
+            .set_tile_extent(2.0);
+
+        let left = builder.build_tile(WangEdges {
+            north: 0,
+            east: 1,
+            south: 1,
+            west: 0,
+        });
+        let right = builder.build_tile(WangEdges {
+            north: 1,
+            east: 0,
+            south: 0,
+            west: 1,
+        });
+        let below = builder.build_tile(WangEdges {
+            north: 1,
+            east: 1,
+            south: 0,
+            west: 0,
+        });
+
+        for i in 0..17 {
+            assert!((left[(16, i)] - right[(0, i)]).abs() < 1e-12);
+            assert!((left[(i, 16)] - below[(i, 0)]).abs() < 1e-12);
+        }
+
+        // A tile with a different east color should not share the east column.
+        assert!((0..17).any(|i| (left[(16, i)] - right[(16, i)]).abs() > 1e-6));
+        assert_eq!(16, builder.build_all().len());
+    }
+}
@@ -0,0 +1,76 @@
+use crate::{
+    utils::{BoundedNoiseMap, NoiseMap},
+    NoiseFn,
+};
+
+/// Noise function that reads its values from a [`NoiseMap`].
+///
+/// The map is placed over the given world-space bounds in the same way
+/// [`PlaneMapBuilder`](crate::utils::PlaneMapBuilder) lays out its samples: cell `(x, y)` lies
+/// at `(x_bounds.0 + x * x_extent / width, y_bounds.0 + y * y_extent / height)`. Points between
+/// cells are bilinearly interpolated, and points outside the map are clamped to its edges.
+#[derive(Clone)]
+pub struct NoiseMapSource {
+    map: BoundedNoiseMap,
+}
+
+impl NoiseMapSource {
+    pub fn new(map: NoiseMap, x_bounds: (f64, f64), y_bounds: (f64, f64)) -> Self {
+        Self {
+            map: BoundedNoiseMap::new(map, x_bounds, y_bounds),
+        }
+    }
+
+    pub fn map(&self) -> &NoiseMap {
+        self.map.map()
+    }
+
+    pub fn x_bounds(&self) -> (f64, f64) {
+        self.map.x_bounds()
+    }
+
+    pub fn y_bounds(&self) -> (f64, f64) {
+        self.map.y_bounds()
+    }
+}
+
+impl From<BoundedNoiseMap> for NoiseMapSource {
+    fn from(map: BoundedNoiseMap) -> Self {
+        Self { map }
+    }
+}
+
+impl NoiseFn<f64, 2> for NoiseMapSource {
+    fn get(&self, point: [f64; 2]) -> f64 {
+        self.map.value_at(point[0], point[1])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        utils::{NoiseMapBuilder, PlaneMapBuilder},
+        Perlin,
+    };
+
+    #[test]
+    fn samples_match_built_cells() {
+        let builder = PlaneMapBuilder::new(Perlin::new(4))
+            .set_size(10, 8)
+            .set_x_bounds(-1.0, 3.0)
+            .set_y_bounds(2.0, 4.0);
+        let (x_map, y_map) = builder.build_coords();
+        let source = NoiseMapSource::new(builder.build(), (-1.0, 3.0), (2.0, 4.0));
+
+        for (x, y) in [(0, 0), (4, 3), (9, 7)] {
+            let value = source.get([x_map[(x, y)], y_map[(x, y)]]);
+
+            assert!((value - source.map()[(x, y)]).abs() < 1e-12);
+        }
+
+        let between = source.get([-0.8, 2.0]);
+        let expected = (source.map()[(0, 0)] + source.map()[(1, 0)]) / 2.0;
+        assert!((between - expected).abs() < 1e-12);
+    }
+}